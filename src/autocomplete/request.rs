@@ -0,0 +1,141 @@
+use crate::models::LatLng;
+use crate::{SearchParams, SendUrl};
+
+#[derive(Debug, Default)]
+pub struct Request {
+    pub url: &'static str,
+    pub token: String,
+
+    /// The text typed so far.
+    pub input: String,
+
+    /// Groups the requests of one search, from the first keystroke to the Place Details of the pick,
+    /// so that they are billed as a single session.
+    pub session_token: Option<String>,
+
+    /// The language in which to return results, such as "en" or "zh-TW".
+    pub language: Option<String>,
+
+    /// Predictions are biased towards this point, within `radius` meters.
+    pub location: Option<LatLng>,
+    pub radius: Option<u32>,
+
+    /// Returns only the places within `location` and `radius` rather than favoring them.
+    pub strict_bounds: bool,
+
+    /// The point from which `distance_meters` of each prediction is measured.
+    pub origin: Option<LatLng>,
+
+    /// Restricts predictions to a type or a type collection, such as "establishment" or "(cities)".
+    pub types: Option<String>,
+
+    /// Restricts predictions to these countries, as ISO 3166-1 Alpha-2 codes such as "au", five at most.
+    pub countries: Vec<String>,
+}
+
+/// A new session token, a version 4 UUID as Google recommends.
+pub fn new_session_token() -> String {
+    uuid::Uuid::new_v4().to_string()
+}
+
+impl Request {
+    pub fn set_session_token(&mut self, session_token: impl Into<String>) -> &mut Self {
+        self.session_token = Some(session_token.into());
+
+        self
+    }
+
+    pub fn set_language(&mut self, language: impl Into<String>) -> &mut Self {
+        self.language = Some(language.into());
+
+        self
+    }
+
+    pub fn set_location(&mut self, location: LatLng, radius: u32) -> &mut Self {
+        self.location = Some(location);
+        self.radius = Some(radius);
+
+        self
+    }
+
+    pub fn set_strict_bounds(&mut self, strict_bounds: bool) -> &mut Self {
+        self.strict_bounds = strict_bounds;
+
+        self
+    }
+
+    pub fn set_origin(&mut self, origin: LatLng) -> &mut Self {
+        self.origin = Some(origin);
+
+        self
+    }
+
+    pub fn set_types(&mut self, types: impl Into<String>) -> &mut Self {
+        self.types = Some(types.into());
+
+        self
+    }
+
+    pub fn add_country(&mut self, country: impl Into<String>) -> &mut Self {
+        self.countries.push(country.into());
+
+        self
+    }
+}
+
+impl SendUrl for Request {
+    fn get_url(&self) -> &'static str {
+        self.url
+    }
+}
+
+impl SearchParams for Request {
+    fn get_params(&self) -> Vec<(String, String)> {
+        let mut params = vec![];
+
+        params.push(("key".to_owned(), self.token.to_owned()));
+
+        params.push(("input".to_owned(), self.input.clone()));
+
+        if let Some(session_token) = &self.session_token {
+            params.push(("sessiontoken".to_owned(), session_token.to_owned()))
+        }
+
+        if let Some(language) = &self.language {
+            params.push(("language".to_owned(), language.to_owned()))
+        }
+
+        if let Some(location) = &self.location {
+            params.push(("location".to_owned(), location.to_string()))
+        }
+
+        if let Some(radius) = self.radius {
+            params.push(("radius".to_owned(), radius.to_string()))
+        }
+
+        if self.strict_bounds {
+            params.push(("strictbounds".to_owned(), "true".to_owned()))
+        }
+
+        if let Some(origin) = &self.origin {
+            params.push(("origin".to_owned(), origin.to_string()))
+        }
+
+        if let Some(types) = &self.types {
+            params.push(("types".to_owned(), types.to_owned()))
+        }
+
+        if !self.countries.is_empty() {
+            let components = self
+                .countries
+                .iter()
+                .map(|country| format!("country:{}", country))
+                .collect::<Vec<_>>()
+                .join("|");
+
+            params.push(("components".to_owned(), components));
+        }
+
+        params
+    }
+}
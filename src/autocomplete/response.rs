@@ -0,0 +1,110 @@
+use crate::models::*;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Response {
+    pub predictions: Vec<Prediction>,
+    pub status: SearchStatus,
+    pub error_message: Option<String>,
+    pub info_messages: Option<Vec<String>>,
+
+    /// Fields returned by Google that this crate does not model yet.
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// A part of a text that matches the input, in characters.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MatchedSubstring {
+    pub length: usize,
+    pub offset: usize,
+}
+
+/// The description split into the name of the place and where it is.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StructuredFormatting {
+    pub main_text: String,
+
+    #[serde(default)]
+    pub main_text_matched_substrings: Vec<MatchedSubstring>,
+
+    pub secondary_text: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Term {
+    pub offset: usize,
+    pub value: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Prediction {
+    /// The human-readable name of the place, such as "Sydney Opera House, Bennelong Point, Sydney NSW, Australia".
+    pub description: String,
+
+    /// Pass it to Place Details, with the session token, to get the place itself.
+    pub place_id: Option<String>,
+
+    /// Meters from the origin of the request, when it has one.
+    pub distance_meters: Option<u32>,
+
+    #[serde(default)]
+    pub matched_substrings: Vec<MatchedSubstring>,
+
+    pub structured_formatting: Option<StructuredFormatting>,
+
+    #[serde(default)]
+    pub terms: Vec<Term>,
+
+    #[serde(default)]
+    pub types: Vec<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Response;
+    use crate::models::SearchStatus;
+
+    #[test]
+    fn test_deserialize_autocomplete_response() {
+        let response: Response = serde_json::from_str(
+            r#"{
+                "predictions": [
+                    {
+                        "description": "Sydney Opera House, Bennelong Point, Sydney NSW, Australia",
+                        "matched_substrings": [{ "length": 9, "offset": 0 }],
+                        "place_id": "ChIJ3S-JXmauEmsRUcIaWtf4MzE",
+                        "reference": "ChIJ3S-JXmauEmsRUcIaWtf4MzE",
+                        "structured_formatting": {
+                            "main_text": "Sydney Opera House",
+                            "main_text_matched_substrings": [{ "length": 9, "offset": 0 }],
+                            "secondary_text": "Bennelong Point, Sydney NSW, Australia"
+                        },
+                        "terms": [
+                            { "offset": 0, "value": "Sydney Opera House" },
+                            { "offset": 20, "value": "Bennelong Point" }
+                        ],
+                        "types": ["tourist_attraction", "establishment"]
+                    }
+                ],
+                "status": "OK"
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(response.status, SearchStatus::Ok);
+
+        let prediction = &response.predictions[0];
+        assert_eq!(
+            prediction.place_id.as_deref(),
+            Some("ChIJ3S-JXmauEmsRUcIaWtf4MzE")
+        );
+        assert_eq!(prediction.matched_substrings[0].length, 9);
+        assert_eq!(
+            prediction.structured_formatting.as_ref().unwrap().main_text,
+            "Sydney Opera House"
+        );
+        assert_eq!(prediction.terms[1].value, "Bennelong Point");
+    }
+}
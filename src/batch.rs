@@ -0,0 +1,224 @@
+use crate::models::Error;
+use crate::Send;
+use futures::stream::{self, LocalBoxStream, StreamExt};
+use std::future::Future;
+
+/// In which order a batch yields its results.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Order {
+    /// In the order the requests were given, waiting for slower ones to finish before yielding later ones.
+    Input,
+
+    /// As soon as each request finishes.
+    Completed,
+}
+
+/// The outcome of one request of a batch.
+#[derive(Debug)]
+pub struct BatchItem<T> {
+    /// Position of the request in the batch, starting at 0.
+    pub index: usize,
+
+    pub result: Result<T, Error>,
+}
+
+/// Runs many requests with at most `concurrency` of them in flight, a failed request does not stop the others.
+pub struct Batch<I> {
+    requests: I,
+
+    /// How many requests may run at the same time.
+    pub concurrency: usize,
+
+    pub order: Order,
+}
+
+impl<I: Iterator> Batch<I> {
+    pub fn new(requests: I) -> Batch<I> {
+        Batch {
+            requests,
+            concurrency: 4,
+            order: Order::Input,
+        }
+    }
+
+    pub fn set_concurrency(mut self, concurrency: usize) -> Batch<I> {
+        self.concurrency = concurrency;
+
+        self
+    }
+
+    pub fn set_order(mut self, order: Order) -> Batch<I> {
+        self.order = order;
+
+        self
+    }
+
+    /// Sends every request, yielding each outcome as a stream.
+    pub fn stream<'a, R>(self) -> LocalBoxStream<'a, BatchItem<R>>
+    where
+        I: 'a,
+        I::Item: Send<R, Error> + 'a,
+        R: 'a,
+    {
+        self.run(|request| async move { request.send().await })
+    }
+
+    /// Sends every request and collects all outcomes.
+    pub async fn send<R>(self) -> Vec<BatchItem<R>>
+    where
+        I::Item: Send<R, Error>,
+    {
+        self.stream().collect().await
+    }
+
+    /// Runs every request through a custom `send`, yielding each outcome as a stream.
+    pub fn run<'a, F, Fut, R>(self, mut send: F) -> LocalBoxStream<'a, BatchItem<R>>
+    where
+        I: 'a,
+        F: FnMut(I::Item) -> Fut + 'a,
+        Fut: Future<Output = Result<R, Error>> + 'a,
+        R: 'a,
+    {
+        let futures = self.requests.enumerate().map(move |(index, request)| {
+            let result = send(request);
+
+            async move {
+                BatchItem {
+                    index,
+                    result: result.await,
+                }
+            }
+        });
+
+        let concurrency = self.concurrency.max(1);
+
+        match self.order {
+            Order::Input => stream::iter(futures).buffered(concurrency).boxed_local(),
+            Order::Completed => stream::iter(futures)
+                .buffer_unordered(concurrency)
+                .boxed_local(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BatchItem, Order};
+    use crate::models::Error;
+    use crate::{Client, Send};
+    use async_trait::async_trait;
+    use futures::StreamExt;
+    use std::cell::Cell;
+    use std::time::Duration;
+
+    /// Answers like `fake_send`, through the `Send` trait `Batch::send` and `Batch::stream` rely on.
+    struct FakeRequest(u64);
+
+    #[async_trait]
+    impl Send<u64, Error> for FakeRequest {
+        async fn send(&self) -> Result<u64, Error> {
+            tokio::time::sleep(Duration::from_millis(20 - self.0)).await;
+
+            answer(self.0)
+        }
+    }
+
+    /// Fails on multiples of 5.
+    fn answer(request: u64) -> Result<u64, Error> {
+        match request % 5 {
+            0 => Err(format!("request {} failed", request).into()),
+            _ => Ok(request * 10),
+        }
+    }
+
+    /// Sleeps longer for earlier requests, fails on multiples of 5, and records how many run at once.
+    async fn fake_send(
+        request: u64,
+        running: &Cell<usize>,
+        peak: &Cell<usize>,
+    ) -> Result<u64, Error> {
+        running.set(running.get() + 1);
+        peak.set(peak.get().max(running.get()));
+
+        tokio::time::sleep(Duration::from_millis(20 - request)).await;
+
+        running.set(running.get() - 1);
+
+        answer(request)
+    }
+
+    fn indices(items: &[BatchItem<u64>]) -> Vec<usize> {
+        items.iter().map(|item| item.index).collect()
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_batch_keeps_input_order() {
+        let (running, peak) = (Cell::new(0), Cell::new(0));
+
+        let items = Client::new("hello kirby")
+            .batch(1..=12)
+            .set_concurrency(3)
+            .run(|request| fake_send(request, &running, &peak))
+            .collect::<Vec<_>>()
+            .await;
+
+        assert_eq!(indices(&items), (0..12).collect::<Vec<_>>());
+        assert_eq!(peak.get(), 3);
+        assert_eq!(items[0].result.as_ref().unwrap(), &10);
+        assert!(items[4].result.is_err());
+        assert!(items[9].result.is_err());
+        assert_eq!(items.iter().filter(|item| item.result.is_ok()).count(), 10);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_batch_yields_as_completed() {
+        let (running, peak) = (Cell::new(0), Cell::new(0));
+
+        let items = Client::new("hello kirby")
+            .batch(1..=6)
+            .set_concurrency(6)
+            .set_order(Order::Completed)
+            .run(|request| fake_send(request, &running, &peak))
+            .collect::<Vec<_>>()
+            .await;
+
+        // later requests sleep less, so they finish first.
+        assert_eq!(indices(&items), vec![5, 4, 3, 2, 1, 0]);
+        assert_eq!(peak.get(), 6);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_batch_send() {
+        let items = Client::new("hello kirby")
+            .batch((1..=12).map(FakeRequest))
+            .set_concurrency(3)
+            .send::<u64>()
+            .await;
+
+        assert_eq!(indices(&items), (0..12).collect::<Vec<_>>());
+        assert_eq!(items[1].result.as_ref().unwrap(), &20);
+        assert_eq!(
+            items[4].result.as_ref().unwrap_err().to_string(),
+            "request 5 failed"
+        );
+        assert_eq!(items.iter().filter(|item| item.result.is_ok()).count(), 10);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_batch_stream_order() {
+        for (order, expected) in [
+            (Order::Input, vec![0, 1, 2, 3, 4, 5]),
+            (Order::Completed, vec![5, 4, 3, 2, 1, 0]),
+        ] {
+            let items = Client::new("hello kirby")
+                .batch((1..=6).map(FakeRequest))
+                .set_concurrency(6)
+                .set_order(order)
+                .stream::<u64>()
+                .collect::<Vec<_>>()
+                .await;
+
+            assert_eq!(indices(&items), expected, "{:?}", order);
+        }
+    }
+}
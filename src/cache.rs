@@ -0,0 +1,96 @@
+use crate::crawl::fnv1a;
+use serde_json::Value;
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+
+type Error = Box<dyn std::error::Error>;
+
+/// Response bodies stored on disk, one file per distinct request.
+///
+/// Requests are told apart by their URL and parameters, the API key left out,
+/// so that the same search made with another key is still served from the cache.
+#[derive(Debug, Clone)]
+pub struct Cache {
+    pub dir: PathBuf,
+
+    /// How long a stored response stays valid.
+    pub ttl: Duration,
+}
+
+impl Cache {
+    pub fn new(dir: impl Into<PathBuf>, ttl: Duration) -> Cache {
+        Cache {
+            dir: dir.into(),
+            ttl,
+        }
+    }
+
+    fn path(&self, url: &str, params: &[(String, String)]) -> PathBuf {
+        let mut request = url.to_owned();
+
+        for (key, value) in params.iter().filter(|(key, _)| key != "key") {
+            request.push_str(&format!("\n{}={}", key, value));
+        }
+
+        self.dir.join(format!("{:016x}.json", fnv1a(&request)))
+    }
+
+    /// The stored body for this request, unless there is none or it expired.
+    pub fn get(&self, url: &str, params: &[(String, String)]) -> Option<Value> {
+        let path = self.path(url, params);
+        let age = fs::metadata(&path)
+            .and_then(|metadata| metadata.modified())
+            .ok()
+            .and_then(|modified| SystemTime::now().duration_since(modified).ok())?;
+
+        if age > self.ttl {
+            return None;
+        }
+
+        serde_json::from_slice(&fs::read(path).ok()?).ok()
+    }
+
+    pub fn put(&self, url: &str, params: &[(String, String)], body: &Value) -> Result<(), Error> {
+        fs::create_dir_all(&self.dir)?;
+        fs::write(self.path(url, params), serde_json::to_vec(body)?)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Cache;
+    use serde_json::json;
+    use std::time::Duration;
+
+    fn params(key: &str, input: &str) -> Vec<(String, String)> {
+        vec![
+            ("key".to_owned(), key.to_owned()),
+            ("input".to_owned(), input.to_owned()),
+        ]
+    }
+
+    #[test]
+    fn test_cache_round_trip() {
+        let dir =
+            std::env::temp_dir().join(format!("google-place-api-cache-{}", std::process::id()));
+        let url = "https://maps.googleapis.com/maps/api/place/findplacefromtext/json";
+        let cache = Cache::new(&dir, Duration::from_secs(60));
+        let body = json!({ "candidates": [], "status": "ZERO_RESULTS" });
+
+        assert_eq!(cache.get(url, &params("dev", "opera")), None);
+
+        cache.put(url, &params("dev", "opera"), &body).unwrap();
+
+        assert_eq!(cache.get(url, &params("prod", "opera")), Some(body));
+        assert_eq!(cache.get(url, &params("dev", "museum")), None);
+
+        let expired = Cache::new(&dir, Duration::ZERO);
+        std::thread::sleep(Duration::from_millis(10));
+        assert_eq!(expired.get(url, &params("dev", "opera")), None);
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
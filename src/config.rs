@@ -0,0 +1,377 @@
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer};
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+type Error = Box<dyn std::error::Error>;
+
+/// The environment variable the API key is read from.
+pub const KEY_VARIABLE: &str = "GOOGLE_PLACES_API_KEY";
+
+/// `$XDG_CONFIG_HOME/google-place-api`, or `~/.config/google-place-api` when XDG_CONFIG_HOME is unset.
+pub fn config_dir() -> Option<PathBuf> {
+    let base = env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+
+    Some(base.join("google-place-api"))
+}
+
+pub fn config_path() -> Option<PathBuf> {
+    config_dir().map(|dir| dir.join("config.toml"))
+}
+
+/// `$XDG_CACHE_HOME/google-place-api`, or `~/.cache/google-place-api` when XDG_CACHE_HOME is unset.
+pub fn cache_dir() -> Option<PathBuf> {
+    let base = env::var_os("XDG_CACHE_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache")))?;
+
+    Some(base.join("google-place-api"))
+}
+
+/// The user config file, such as:
+///
+/// ```toml
+/// key = "fallback key"
+/// default_profile = "dev"
+///
+/// [profiles.dev]
+/// key = "dev key"
+/// language = "en"
+/// fields = ["name", "geometry", "place_id"]
+/// output = "table"
+/// rate_limit = 2.0
+///
+/// [profiles.dev.cache]
+/// ttl = 86400
+/// ```
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    /// Google API key, used when the profile has none.
+    pub key: Option<String>,
+
+    /// The profile used when --profile is not given.
+    pub default_profile: Option<String>,
+
+    #[serde(default)]
+    pub profiles: BTreeMap<String, Profile>,
+}
+
+/// Defaults for every command run with this profile, command-line options take precedence.
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Profile {
+    pub key: Option<String>,
+
+    /// Language of the results, such as "en" or "zh-TW".
+    pub language: Option<String>,
+
+    /// Fields returned by Find Place, as named by the --fields option.
+    #[serde(default)]
+    pub fields: Vec<String>,
+
+    /// Output format, as named by the --output option.
+    pub output: Option<String>,
+
+    /// Requests sent per second at most, a number above 0.
+    #[serde(default, deserialize_with = "positive")]
+    pub rate_limit: Option<f64>,
+
+    /// Responses are cached on disk when set.
+    pub cache: Option<CacheSettings>,
+}
+
+fn positive<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<f64>, D::Error> {
+    match Option::<f64>::deserialize(deserializer)? {
+        Some(value) if value.is_nan() || value <= 0.0 => Err(D::Error::custom(format!(
+            "expected a number above 0, found {}",
+            value
+        ))),
+        value => Ok(value),
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CacheSettings {
+    /// Where responses are stored, the user cache directory by default.
+    pub dir: Option<PathBuf>,
+
+    /// Seconds a response stays valid.
+    #[serde(default = "CacheSettings::default_ttl")]
+    pub ttl: u64,
+}
+
+impl CacheSettings {
+    fn default_ttl() -> u64 {
+        24 * 60 * 60
+    }
+
+    pub fn cache(&self) -> Result<google_place_api::cache::Cache, Error> {
+        let dir = match &self.dir {
+            Some(dir) => dir.clone(),
+            None => cache_dir()
+                .ok_or("no cache directory, as neither XDG_CACHE_HOME nor HOME is set")?,
+        };
+
+        Ok(google_place_api::cache::Cache::new(
+            dir,
+            Duration::from_secs(self.ttl),
+        ))
+    }
+}
+
+impl Config {
+    /// Reads the config file, `None` when it does not exist.
+    pub fn load(path: &Path) -> Result<Option<Config>, Error> {
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let text = fs::read_to_string(path)?;
+        let config = toml::from_str(&text)
+            .map_err(|error| format!("invalid config file {}: {}", path.display(), error))?;
+
+        Ok(Some(config))
+    }
+
+    /// The profile named `name`, or the default one when no name is given.
+    /// A profile asked for by name must exist.
+    pub fn profile(&self, name: Option<&str>) -> Result<Option<(&str, &Profile)>, Error> {
+        let name = match name.or(self.default_profile.as_deref()) {
+            Some(name) => name,
+            None => return Ok(None),
+        };
+
+        match self.profiles.get_key_value(name) {
+            Some((name, profile)) => Ok(Some((name.as_str(), profile))),
+            None => Err(format!(
+                "no profile named {:?}, the config file has: {}",
+                name,
+                self.profiles.keys().cloned().collect::<Vec<_>>().join(", ")
+            )
+            .into()),
+        }
+    }
+}
+
+fn non_empty(key: &str) -> Option<String> {
+    Some(key.trim())
+        .filter(|key| !key.is_empty())
+        .map(str::to_owned)
+}
+
+/// Where the API key can come from, in order of precedence:
+///
+/// 1. `--token`
+/// 2. `--key-file`
+/// 3. the key of a profile picked with `--profile`
+/// 4. GOOGLE_PLACES_API_KEY
+/// 5. the key of the `default_profile`
+/// 6. the top-level key of the config file
+///
+/// A key set up for a single shell with the environment variable thus beats the config file,
+/// unless a profile is asked for by name.
+#[derive(Debug, Default)]
+pub struct KeySources<'a> {
+    /// `--token` on the command line.
+    pub token: Option<&'a str>,
+
+    /// `--key-file`, a file holding nothing but the key.
+    pub key_file: Option<&'a Path>,
+
+    /// The selected profile, by name, and its key.
+    pub profile: Option<(&'a str, Option<&'a str>)>,
+
+    /// Whether the profile was picked with `--profile` rather than being the `default_profile`.
+    pub explicit_profile: bool,
+
+    /// The value of GOOGLE_PLACES_API_KEY.
+    pub variable: Option<&'a str>,
+
+    /// The user config file, and its top-level key.
+    pub config: Option<(&'a Path, Option<&'a str>)>,
+}
+
+impl KeySources<'_> {
+    /// The first key found. A key file given explicitly must be readable,
+    /// and the error lists every place that was looked at when there is no key at all.
+    pub fn resolve(&self) -> Result<String, Error> {
+        if let Some(key) = self.token.and_then(non_empty) {
+            return Ok(key);
+        }
+
+        if let Some(path) = self.key_file {
+            let text = fs::read_to_string(path)
+                .map_err(|error| format!("cannot read key file {}: {}", path.display(), error))?;
+
+            return non_empty(&text)
+                .ok_or_else(|| format!("key file {} is empty", path.display()).into());
+        }
+
+        let profile_key = self.profile.and_then(|(_, key)| key).and_then(non_empty);
+        let variable_key = self.variable.and_then(non_empty);
+        let config_key = self.config.and_then(|(_, key)| key).and_then(non_empty);
+
+        let key = if self.explicit_profile {
+            profile_key.or(variable_key)
+        } else {
+            variable_key.or(profile_key)
+        };
+
+        if let Some(key) = key.or(config_key) {
+            return Ok(key);
+        }
+
+        let mut looked = vec![
+            "the --token option".to_owned(),
+            "a file given with --key-file".to_owned(),
+        ];
+
+        let variable = format!("the {} environment variable", KEY_VARIABLE);
+        let profile = self
+            .profile
+            .map(|(name, _)| format!("a `key` entry in the {:?} profile", name));
+
+        if self.explicit_profile {
+            looked.extend(profile);
+            looked.push(variable);
+        } else {
+            looked.push(variable);
+            looked.extend(profile);
+        }
+        looked.push(match self.config {
+            Some((path, _)) => format!("a `key` entry in {}", path.display()),
+            None => "a config file, as neither XDG_CONFIG_HOME nor HOME is set".to_owned(),
+        });
+
+        Err(format!(
+            "no Google API key found, looked for:\n  - {}",
+            looked.join("\n  - ")
+        )
+        .into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Config, KeySources};
+    use std::path::Path;
+
+    const CONFIG: &str = r#"
+        key = "config-key"
+        default_profile = "dev"
+
+        [profiles.dev]
+        key = "dev-key"
+        language = "en"
+        fields = ["name", "place_id"]
+        output = "table"
+        rate_limit = 2.0
+
+        [profiles.prod]
+        key = "prod-key"
+
+        [profiles.prod.cache]
+        dir = "/tmp/places"
+    "#;
+
+    #[test]
+    fn test_profiles() {
+        let config: Config = toml::from_str(CONFIG).unwrap();
+
+        let (name, dev) = config.profile(None).unwrap().unwrap();
+        assert_eq!(name, "dev");
+        assert_eq!(dev.language.as_deref(), Some("en"));
+        assert_eq!(dev.fields, vec!["name", "place_id"]);
+        assert_eq!(dev.rate_limit, Some(2.0));
+        assert!(dev.cache.is_none());
+
+        let (_, prod) = config.profile(Some("prod")).unwrap().unwrap();
+        let cache = prod.cache.as_ref().unwrap();
+        assert_eq!(cache.ttl, 86400);
+        assert_eq!(cache.cache().unwrap().dir, Path::new("/tmp/places"));
+
+        let error = config.profile(Some("staging")).unwrap_err().to_string();
+        assert!(error.contains("dev, prod"));
+
+        assert!(toml::from_str::<Config>("[profiles.dev]\nlanguages = \"en\"").is_err());
+
+        for rate_limit in ["0.0", "-1.0", "nan"] {
+            let text = format!("[profiles.dev]\nrate_limit = {}", rate_limit);
+            let error = toml::from_str::<Config>(&text).unwrap_err().to_string();
+            assert!(error.contains("expected a number above 0"), "{}", error);
+        }
+    }
+
+    #[test]
+    fn test_key_precedence() {
+        let key_file =
+            std::env::temp_dir().join(format!("google-place-api-key-{}", std::process::id()));
+        std::fs::write(&key_file, "file-key\n").unwrap();
+
+        let config = Path::new("config.toml");
+        let mut sources = KeySources {
+            token: Some("token-key"),
+            key_file: Some(&key_file),
+            profile: Some(("dev", Some("profile-key"))),
+            explicit_profile: true,
+            variable: Some("variable-key"),
+            config: Some((config, Some("config-key"))),
+        };
+        assert_eq!(sources.resolve().unwrap(), "token-key");
+
+        sources.token = None;
+        assert_eq!(sources.resolve().unwrap(), "file-key");
+
+        sources.key_file = None;
+        assert_eq!(sources.resolve().unwrap(), "profile-key");
+
+        // the default profile does not beat the environment.
+        sources.explicit_profile = false;
+        assert_eq!(sources.resolve().unwrap(), "variable-key");
+
+        sources.variable = None;
+        assert_eq!(sources.resolve().unwrap(), "profile-key");
+
+        sources.profile = Some(("dev", None));
+        sources.variable = Some("  ");
+        assert_eq!(sources.resolve().unwrap(), "config-key");
+
+        std::fs::remove_file(key_file).unwrap();
+    }
+
+    #[test]
+    fn test_missing_key() {
+        let missing = Path::new("/nonexistent/config.toml");
+
+        let error = KeySources {
+            profile: Some(("dev", None)),
+            config: Some((missing, None)),
+            ..Default::default()
+        }
+        .resolve()
+        .unwrap_err()
+        .to_string();
+
+        assert!(error.contains("GOOGLE_PLACES_API_KEY"));
+        assert!(error.contains("\"dev\" profile"));
+        assert!(error.contains("/nonexistent/config.toml"));
+
+        // an explicit key file must exist, even when the environment has a key.
+        let error = KeySources {
+            key_file: Some(missing),
+            variable: Some("variable-key"),
+            ..Default::default()
+        }
+        .resolve();
+        assert!(error.is_err());
+    }
+}
@@ -0,0 +1,740 @@
+use crate::models::{Bounds, Error, Place, SearchStatus};
+use crate::nearby::{self, Page};
+use crate::sweep::{Circle, Sweep, SweepResult, RESULT_LIMIT};
+use crate::SearchParams;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, VecDeque};
+use std::fs::{self, File, OpenOptions};
+use std::future::Future;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// 64-bit FNV-1a, stable across Rust versions unlike the standard library's hasher.
+pub(crate) fn fnv1a(text: &str) -> u64 {
+    text.bytes().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+/// A cell whose first pages were fetched, and the token of its next page.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CellProgress {
+    pub cell: Bounds,
+    pub next_page_token: String,
+
+    /// Results returned so far for this cell, including places kept from other cells.
+    pub results: usize,
+}
+
+/// One line of the checkpoint file.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum Entry {
+    /// The first line, telling which crawl the file belongs to and the cells it starts with.
+    Start {
+        fingerprint: String,
+        cells: Vec<Bounds>,
+    },
+
+    /// A page fetched for `cell`, with the places it found that were not known yet.
+    /// Without a next page the cell is done, and split into `quadrants` when it returned too many results.
+    Page {
+        cell: Bounds,
+        places: Vec<Place>,
+        results: usize,
+        next_page_token: Option<String>,
+        quadrants: Vec<Bounds>,
+    },
+
+    /// A request for `cell` failed, the cell is searched again from its first page by the next run.
+    Failed { cell: Bounds },
+}
+
+/// Everything a crawl has done so far, replayed from the checkpoint file it appends a line to after every request.
+#[derive(Debug, Default)]
+pub struct Checkpoint {
+    /// Identifies the region and search parameters of the crawl, see `Crawl::fingerprint`.
+    pub fingerprint: String,
+
+    /// Cells still to search, including the quadrants of subdivided cells.
+    pub pending: VecDeque<Bounds>,
+
+    /// Cells searched through their last page.
+    pub completed: Vec<Bounds>,
+
+    /// The cell being paged through when the checkpoint was written.
+    pub current: Option<CellProgress>,
+
+    /// Cells whose request failed during this run, put back with the pending cells when the checkpoint is loaded.
+    pub failed: Vec<Bounds>,
+
+    pub place_ids: BTreeSet<String>,
+    pub places: Vec<Place>,
+
+    /// Number of requests sent to the Nearby Search API, each page counting as one.
+    pub requests: usize,
+
+    /// Number of cells that returned a full 60 results and were split into four.
+    pub subdivided: usize,
+
+    /// Cells that still returned a full 60 results but could not be split below `min_radius`.
+    pub saturated: Vec<Bounds>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Progress {
+    pub cells_done: usize,
+    pub cells_pending: usize,
+    pub places_found: usize,
+    pub requests: usize,
+}
+
+impl Checkpoint {
+    pub fn progress(&self) -> Progress {
+        Progress {
+            cells_done: self.completed.len(),
+            cells_pending: self.pending.len() + self.current.iter().count() + self.failed.len(),
+            places_found: self.places.len(),
+            requests: self.requests,
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.pending.is_empty() && self.current.is_none() && self.failed.is_empty()
+    }
+
+    /// The cell to search next, the token of its next page and the results it returned so far.
+    fn next_cell(&self) -> Option<(Bounds, Option<String>, usize)> {
+        match &self.current {
+            Some(progress) => Some((
+                progress.cell,
+                Some(progress.next_page_token.clone()),
+                progress.results,
+            )),
+            None => self.pending.front().map(|cell| (*cell, None, 0)),
+        }
+    }
+
+    /// Takes `cell` out of the current, pending or failed cells, wherever it is.
+    fn remove(&mut self, cell: Bounds) {
+        if self
+            .current
+            .as_ref()
+            .is_some_and(|progress| progress.cell == cell)
+        {
+            self.current = None;
+        } else if let Some(index) = self.pending.iter().position(|pending| *pending == cell) {
+            self.pending.remove(index);
+        } else {
+            self.failed.retain(|failed| *failed != cell);
+        }
+    }
+
+    fn is_new(&self, place: &Place) -> bool {
+        place
+            .place_id
+            .as_ref()
+            .is_none_or(|place_id| !self.place_ids.contains(place_id))
+    }
+
+    fn apply(&mut self, entry: Entry) {
+        match entry {
+            Entry::Start { fingerprint, cells } => {
+                self.fingerprint = fingerprint;
+                self.pending = cells.into();
+            }
+            Entry::Page {
+                cell,
+                places,
+                results,
+                next_page_token,
+                quadrants,
+            } => {
+                self.remove(cell);
+                self.requests += 1;
+
+                for place in places {
+                    if self.is_new(&place) {
+                        self.place_ids.extend(place.place_id.clone());
+                        self.places.push(place);
+                    }
+                }
+
+                match next_page_token {
+                    Some(next_page_token) => {
+                        self.current = Some(CellProgress {
+                            cell,
+                            next_page_token,
+                            results,
+                        })
+                    }
+                    None => {
+                        if !quadrants.is_empty() {
+                            self.subdivided += 1;
+                            self.pending.extend(quadrants);
+                        } else if results >= RESULT_LIMIT {
+                            self.saturated.push(cell);
+                        }
+
+                        self.completed.push(cell);
+                    }
+                }
+            }
+            Entry::Failed { cell } => {
+                self.remove(cell);
+                self.failed.push(cell);
+            }
+        }
+    }
+
+    /// Replays the checkpoint file. A last line cut short by a crash is ignored, losing the request it recorded.
+    /// Cells whose request failed are pending again.
+    pub fn load(path: impl AsRef<Path>) -> Result<Checkpoint, Error> {
+        let text = fs::read_to_string(path)?;
+        let mut checkpoint = Checkpoint::default();
+
+        for line in text.split_inclusive('\n') {
+            if line.ends_with('\n') {
+                checkpoint.apply(serde_json::from_str(line)?);
+            }
+        }
+
+        let failed = std::mem::take(&mut checkpoint.failed);
+        checkpoint.pending.extend(failed);
+
+        Ok(checkpoint)
+    }
+}
+
+/// The checkpoint file, opened to append one JSON line per entry.
+struct Log {
+    file: File,
+    is_empty: bool,
+}
+
+impl Log {
+    /// Opens or creates the file, dropping a last line cut short by a crash.
+    fn open(path: &Path) -> Result<Log, Error> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        let text = fs::read(path)?;
+        let complete = text
+            .iter()
+            .rposition(|&byte| byte == b'\n')
+            .map_or(0, |end| end + 1);
+
+        if complete < text.len() {
+            file.set_len(complete as u64)?;
+        }
+
+        Ok(Log {
+            file,
+            is_empty: complete == 0,
+        })
+    }
+
+    fn append(&mut self, entry: &Entry) -> Result<(), Error> {
+        let mut line = serde_json::to_vec(entry)?;
+        line.push(b'\n');
+
+        self.file.write_all(&line)?;
+        self.is_empty = false;
+
+        Ok(())
+    }
+}
+
+impl Sweep {
+    /// Turns the sweep into a crawl checkpointed to the file at `checkpoint`.
+    pub fn crawl(self, checkpoint: impl Into<PathBuf>) -> Crawl {
+        Crawl::new(self, checkpoint)
+    }
+}
+
+/// A sweep that records its progress to a checkpoint file, and picks up from it when run again.
+///
+/// Cells are searched one page at a time so that a crash loses at most one request,
+/// each request appending a line with what it found to the checkpoint.
+/// Requests are sent one after the other, `sweep.concurrency` is not used by the crawl.
+/// The checkpoint is kept once the crawl finishes, running it again returns the collected places for free.
+pub struct Crawl {
+    pub sweep: Sweep,
+    pub checkpoint: PathBuf,
+}
+
+impl Crawl {
+    pub fn new(sweep: Sweep, checkpoint: impl Into<PathBuf>) -> Crawl {
+        Crawl {
+            sweep,
+            checkpoint: checkpoint.into(),
+        }
+    }
+
+    /// Identifies the region, the cell sizes and the search parameters, the API key aside,
+    /// so that a checkpoint is never resumed by a different crawl.
+    pub fn fingerprint(&self) -> String {
+        let params = self
+            .sweep
+            .request
+            .get_params()
+            .into_iter()
+            .filter(|(name, _)| name != "key" && name != "location")
+            .collect::<Vec<_>>();
+
+        let description = format!(
+            "{:?} {:?} {} {}",
+            self.sweep.region, params, self.sweep.cell_radius, self.sweep.min_radius
+        );
+
+        format!("{:016x}", fnv1a(&description))
+    }
+
+    /// Reads the checkpoint file, or plans a new crawl when there is none yet.
+    /// Fails when the checkpoint was written by a crawl of another region or with other parameters.
+    pub fn load(&self) -> Result<Checkpoint, Error> {
+        let fingerprint = self.fingerprint();
+
+        if self.checkpoint.exists() {
+            let checkpoint = Checkpoint::load(&self.checkpoint)?;
+
+            if checkpoint.fingerprint == fingerprint {
+                return Ok(checkpoint);
+            }
+
+            // a crash before the first line was written leaves nothing to resume.
+            if !checkpoint.fingerprint.is_empty() {
+                return Err(format!(
+                    "{} was written by a crawl of another region or with other parameters, remove it to start over",
+                    self.checkpoint.display()
+                )
+                .into());
+            }
+        }
+
+        Ok(Checkpoint {
+            fingerprint,
+            pending: self.sweep.cells().into(),
+            ..Default::default()
+        })
+    }
+
+    /// Runs the crawl against the Nearby Search API, calling `on_progress` after every request.
+    pub async fn send(&self, on_progress: impl FnMut(&Progress)) -> Result<SweepResult, Error> {
+        self.run(
+            |circle, page| {
+                let mut request = self.sweep.request.clone();
+                request.location = circle.center;
+
+                async move {
+                    let request = request.prominence(circle.search_radius());
+
+                    nearby::fetch_page(&request, page).await
+                }
+            },
+            on_progress,
+        )
+        .await
+    }
+
+    /// Runs the crawl with a custom `fetch`, which returns the given page of a search within a circle.
+    ///
+    /// A failing request does not stop the crawl, its cell is recorded in `SweepResult::failed`
+    /// and searched again from its first page by the next run.
+    /// Only reading or writing the checkpoint makes the crawl fail.
+    pub async fn run<F, Fut, P>(&self, fetch: F, mut on_progress: P) -> Result<SweepResult, Error>
+    where
+        F: Fn(Circle, Page) -> Fut,
+        Fut: Future<Output = Result<nearby::Response, Error>>,
+        P: FnMut(&Progress),
+    {
+        let mut checkpoint = self.load()?;
+        let mut log = Log::open(&self.checkpoint)?;
+
+        if log.is_empty {
+            log.append(&Entry::Start {
+                fingerprint: checkpoint.fingerprint.clone(),
+                cells: checkpoint.pending.iter().copied().collect(),
+            })?;
+        }
+
+        let mut failed = vec![];
+
+        // a page token saved before a crash may have expired, the cell is then searched again from its first page.
+        let mut resumed = checkpoint.current.is_some();
+
+        while let Some((cell, token, mut results)) = checkpoint.next_cell() {
+            let circle = Circle {
+                center: cell.center(),
+                radius: cell.covering_radius(),
+            };
+
+            let page = match token {
+                Some(token) if resumed => Page::Saved(token),
+                Some(token) => Page::Next(token),
+                None => Page::First,
+            };
+
+            let mut response = fetch(circle, page).await;
+
+            if response.is_err() && resumed {
+                results = 0;
+                response = fetch(circle, Page::First).await;
+            }
+
+            resumed = false;
+
+            let response = response.and_then(|response| match response.status {
+                SearchStatus::Ok | SearchStatus::ZeroResults => Ok(response),
+                status => Err(format!(
+                    "nearby search around {} failed with {:?}: {}",
+                    circle.center,
+                    status,
+                    response.error_message.unwrap_or_default()
+                )
+                .into()),
+            });
+
+            let response = match response {
+                Ok(response) => response,
+                Err(error) => {
+                    let entry = Entry::Failed { cell };
+
+                    log.append(&entry)?;
+                    checkpoint.apply(entry);
+                    failed.push((circle, error));
+
+                    on_progress(&checkpoint.progress());
+                    continue;
+                }
+            };
+
+            results += response.results.len();
+
+            let next_page_token = response
+                .next_page_token
+                .filter(|_| response.status == SearchStatus::Ok);
+
+            let quadrants = if next_page_token.is_none()
+                && results >= RESULT_LIMIT
+                && circle.radius / 2.0 >= self.sweep.min_radius as f64
+            {
+                cell.split(2, 2)
+                    .into_iter()
+                    .filter(|quadrant| self.sweep.region.intersects(quadrant))
+                    .collect()
+            } else {
+                vec![]
+            };
+
+            let entry = Entry::Page {
+                cell,
+                places: response
+                    .results
+                    .into_iter()
+                    .filter(|place| self.sweep.keeps(place) && checkpoint.is_new(place))
+                    .collect(),
+                results,
+                next_page_token,
+                quadrants,
+            };
+
+            log.append(&entry)?;
+            checkpoint.apply(entry);
+
+            on_progress(&checkpoint.progress());
+        }
+
+        Ok(SweepResult {
+            searches: checkpoint.completed.len() + failed.len(),
+            subdivided: checkpoint.subdivided,
+            saturated: checkpoint.saturated,
+            places: checkpoint.places,
+            failed,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Checkpoint, Progress};
+    use crate::models::{Bounds, Error, LatLng};
+    use crate::nearby::{self, Page};
+    use crate::sweep::{Circle, RESULT_LIMIT};
+    use crate::testing;
+    use crate::Client;
+    use std::cell::{Cell, RefCell};
+    use std::path::PathBuf;
+
+    const PAGE_SIZE: usize = 20;
+
+    /// A 12 by 12 grid of places, about 50 meters apart, in central Sydney.
+    fn places() -> Vec<LatLng> {
+        (0..12)
+            .flat_map(|row| (0..12).map(move |col| (row, col)))
+            .map(|(row, col)| {
+                LatLng::new(-33.88 + row as f64 * 0.00045, 151.20 + col as f64 * 0.00054)
+            })
+            .collect()
+    }
+
+    fn area() -> Bounds {
+        Bounds::new(
+            LatLng::new(-33.8801, 151.1999),
+            LatLng::new(-33.8749, 151.2061),
+        )
+    }
+
+    /// Behaves like Nearby Search: up to 60 places within the circle, 20 per page, the token being the page number.
+    async fn fetch(circle: Circle, page: Page) -> Result<nearby::Response, Error> {
+        let page = match page {
+            Page::First => 0,
+            Page::Next(token) | Page::Saved(token) => token.parse::<usize>().unwrap(),
+        };
+
+        let found = places()
+            .iter()
+            .enumerate()
+            .filter(|(_, location)| circle.contains(location))
+            .take(RESULT_LIMIT)
+            .map(|(index, location)| (format!("place-{}", index), *location))
+            .collect::<Vec<_>>();
+
+        let results = found
+            .iter()
+            .skip(page * PAGE_SIZE)
+            .take(PAGE_SIZE)
+            .map(|(place_id, location)| {
+                testing::place_value(*location, serde_json::json!({ "place_id": place_id }))
+            })
+            .collect::<Vec<_>>();
+
+        let next_page_token =
+            ((page + 1) * PAGE_SIZE < found.len()).then(|| (page + 1).to_string());
+
+        Ok(serde_json::from_value(serde_json::json!({
+            "html_attributions": [],
+            "results": results,
+            "status": if found.is_empty() { "ZERO_RESULTS" } else { "OK" },
+            "next_page_token": next_page_token
+        }))?)
+    }
+
+    fn checkpoint_path(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!(
+            "google-place-api-{}-{}.ndjson",
+            name,
+            std::process::id()
+        ));
+
+        let _ = std::fs::remove_file(&path);
+
+        path
+    }
+
+    #[tokio::test]
+    async fn test_crawl_collects_and_reports_progress() {
+        let path = checkpoint_path("crawl");
+        let crawl = Client::new("hello kirby")
+            .sweep(area())
+            .set_cell_radius(1000)
+            .crawl(&path);
+
+        let mut reports: Vec<Progress> = vec![];
+        let result = crawl
+            .run(fetch, |progress| reports.push(*progress))
+            .await
+            .unwrap();
+
+        assert_eq!(result.places.len(), 144);
+        assert!(result.subdivided > 0);
+
+        let last = reports.last().unwrap();
+        assert_eq!(last.places_found, 144);
+        assert_eq!(last.cells_pending, 0);
+        assert_eq!(last.cells_done, result.searches);
+        assert_eq!(last.requests, reports.len());
+
+        let checkpoint = Checkpoint::load(&path).unwrap();
+        assert!(checkpoint.is_finished());
+        assert_eq!(checkpoint.place_ids.len(), 144);
+
+        // a line per request after the first one, each place written once.
+        let log = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(log.lines().count(), checkpoint.requests + 1);
+        assert_eq!(log.matches("\"place_id\"").count(), 144);
+
+        // a finished crawl costs nothing to run again.
+        let again = crawl
+            .run(fetch, |_| panic!("no request expected"))
+            .await
+            .unwrap();
+        assert_eq!(again.places.len(), 144);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_crawl_resumes_after_failure() {
+        let path = checkpoint_path("resume");
+        let crawl = Client::new("hello kirby")
+            .sweep(area())
+            .set_cell_radius(1000)
+            .crawl(&path);
+
+        let calls = Cell::new(0);
+        let failing = |circle, page| {
+            calls.set(calls.get() + 1);
+            let fail = calls.get() == 8;
+
+            async move {
+                if fail {
+                    return Err("connection reset".into());
+                }
+
+                fetch(circle, page).await
+            }
+        };
+
+        let interrupted = crawl.run(failing, |_| {}).await.unwrap();
+        assert_eq!(interrupted.failed.len(), 1);
+        assert_eq!(interrupted.failed[0].1.to_string(), "connection reset");
+        assert!(interrupted.places.len() < 144);
+
+        // every request but the failed one was written, and its cell is left to the next run.
+        let checkpoint = Checkpoint::load(&path).unwrap();
+        assert_eq!(checkpoint.requests, calls.get() - 1);
+        assert!(checkpoint.failed.is_empty());
+        assert!(!checkpoint.is_finished());
+
+        let result = crawl.run(fetch, |_| {}).await.unwrap();
+        let resumed = Checkpoint::load(&path).unwrap();
+
+        let fresh_path = checkpoint_path("fresh");
+        Client::new("hello kirby")
+            .sweep(area())
+            .set_cell_radius(1000)
+            .crawl(&fresh_path)
+            .run(fetch, |_| {})
+            .await
+            .unwrap();
+        let fresh = Checkpoint::load(&fresh_path).unwrap();
+
+        assert_eq!(result.places.len(), 144);
+        assert!(result.failed.is_empty());
+        assert!(resumed.is_finished());
+        assert_eq!(resumed.subdivided, fresh.subdivided);
+
+        std::fs::remove_file(&path).unwrap();
+        std::fs::remove_file(&fresh_path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_crawl_ignores_truncated_line() {
+        let path = checkpoint_path("truncated");
+        let crawl = Client::new("hello kirby")
+            .sweep(area())
+            .set_cell_radius(1000)
+            .crawl(&path);
+
+        let calls = Cell::new(0);
+        let failing = |circle, page| {
+            calls.set(calls.get() + 1);
+            let fail = calls.get() == 4;
+
+            async move {
+                if fail {
+                    return Err("connection reset".into());
+                }
+
+                fetch(circle, page).await
+            }
+        };
+
+        assert_eq!(crawl.run(failing, |_| {}).await.unwrap().failed.len(), 1);
+        let requests = Checkpoint::load(&path).unwrap().requests;
+
+        // a crash while the last line was being written.
+        let mut log = std::fs::read_to_string(&path).unwrap();
+        log.push_str("{\"page\":{\"cell\":");
+        std::fs::write(&path, log).unwrap();
+
+        assert_eq!(Checkpoint::load(&path).unwrap().requests, requests);
+
+        let result = crawl.run(fetch, |_| {}).await.unwrap();
+        assert_eq!(result.places.len(), 144);
+        assert!(Checkpoint::load(&path).unwrap().is_finished());
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_crawl_restarts_cell_of_expired_token() {
+        let path = checkpoint_path("expired");
+        let crawl = Client::new("hello kirby")
+            .sweep(area())
+            .set_cell_radius(1000)
+            .crawl(&path);
+
+        crawl.run(fetch, |_| {}).await.unwrap();
+
+        // a crash right after the first page, whose token has expired since.
+        let log = std::fs::read_to_string(&path).unwrap();
+        let head = log.split_inclusive('\n').take(2).collect::<String>();
+        std::fs::write(&path, head).unwrap();
+        assert!(Checkpoint::load(&path).unwrap().current.is_some());
+
+        let pages = RefCell::new(vec![]);
+        let expired = |circle, page: Page| {
+            pages.borrow_mut().push(page.clone());
+
+            async move {
+                match page {
+                    Page::Saved(_) => Err("INVALID_REQUEST".into()),
+                    page => fetch(circle, page).await,
+                }
+            }
+        };
+
+        let result = crawl.run(expired, |_| {}).await.unwrap();
+        assert!(result.failed.is_empty());
+        assert_eq!(result.places.len(), 144);
+
+        let pages = pages.borrow();
+        assert_eq!(pages[0], Page::Saved("1".to_owned()));
+        assert_eq!(pages[1], Page::First);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_crawl_refuses_other_checkpoint() {
+        let path = checkpoint_path("fingerprint");
+        let client = Client::new("hello kirby");
+
+        client
+            .sweep(area())
+            .set_keyword("pizza")
+            .crawl(&path)
+            .run(fetch, |_| {})
+            .await
+            .unwrap();
+
+        let other_key = Client::new("another key")
+            .sweep(area())
+            .set_keyword("pizza");
+        assert!(other_key.crawl(&path).run(fetch, |_| {}).await.is_ok());
+
+        let other_keyword = client.sweep(area()).set_keyword("sushi").crawl(&path);
+        assert!(other_keyword.run(fetch, |_| {}).await.is_err());
+
+        let other_area = client
+            .sweep(Bounds::new(
+                LatLng::new(-33.8801, 151.1999),
+                LatLng::new(-33.8700, 151.2061),
+            ))
+            .set_keyword("pizza")
+            .crawl(&path);
+        assert!(other_area.load().is_err());
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,130 @@
+use crate::{SearchParams, SendUrl};
+use clap::ArgEnum;
+use strum_macros::Display;
+
+#[derive(Debug, Default)]
+pub struct Request {
+    pub url: &'static str,
+    pub token: String,
+    pub place_id: String,
+
+    /// Every field is returned, and billed, when none is given.
+    pub fields: Vec<Field>,
+
+    /// The language in which to return results, such as "en" or "zh-TW".
+    pub language: Option<String>,
+
+    /// Ties the request to the Place Autocomplete session that found the place, for billing.
+    pub session_token: Option<String>,
+}
+
+/// Place Details takes every Find Place field, plus the contact and atmosphere ones.
+#[derive(Clone, ArgEnum, Display, Debug)]
+#[strum(serialize_all = "snake_case")]
+pub enum Field {
+    AddressComponent,
+    AdrAddress,
+    BusinessStatus,
+    FormattedAddress,
+    Geometry,
+    Icon,
+    IconMaskBaseUri,
+    IconBackgroundColor,
+    Name,
+    Photo,
+    PlaceId,
+    PlusCode,
+    Type,
+    Url,
+    UtcOffset,
+    Vicinity,
+    WheelchairAccessibleEntrance,
+
+    CurrentOpeningHours,
+    FormattedPhoneNumber,
+    InternationalPhoneNumber,
+    OpeningHours,
+    SecondaryOpeningHours,
+    Website,
+
+    CurbsidePickup,
+    Delivery,
+    DineIn,
+    EditorialSummary,
+    PriceLevel,
+    Rating,
+    Reservable,
+    Reviews,
+    ServesBeer,
+    ServesBreakfast,
+    ServesBrunch,
+    ServesDinner,
+    ServesLunch,
+    ServesVegetarianFood,
+    ServesWine,
+    Takeout,
+    UserRatingsTotal,
+}
+
+impl Request {
+    pub fn add_field(&mut self, field: Field) -> &mut Self {
+        self.fields.push(field);
+
+        self
+    }
+
+    pub fn add_fields(&mut self, fields: Vec<Field>) -> &mut Self {
+        self.fields.extend(fields);
+
+        self
+    }
+
+    pub fn set_language(&mut self, language: impl Into<String>) -> &mut Self {
+        self.language = Some(language.into());
+
+        self
+    }
+
+    pub fn set_session_token(&mut self, session_token: impl Into<String>) -> &mut Self {
+        self.session_token = Some(session_token.into());
+
+        self
+    }
+}
+
+impl SendUrl for Request {
+    fn get_url(&self) -> &'static str {
+        self.url
+    }
+}
+
+impl SearchParams for Request {
+    fn get_params(&self) -> Vec<(String, String)> {
+        let mut params = vec![];
+
+        params.push(("key".to_owned(), self.token.to_owned()));
+
+        params.push(("place_id".to_owned(), self.place_id.clone()));
+
+        if !self.fields.is_empty() {
+            let fields = self
+                .fields
+                .iter()
+                .map(|field| field.to_string())
+                .collect::<Vec<_>>()
+                .join(",");
+
+            params.push(("fields".to_owned(), fields));
+        }
+
+        if let Some(language) = &self.language {
+            params.push(("language".to_owned(), language.to_owned()))
+        }
+
+        if let Some(session_token) = &self.session_token {
+            params.push(("sessiontoken".to_owned(), session_token.to_owned()))
+        }
+
+        params
+    }
+}
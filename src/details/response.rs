@@ -0,0 +1,68 @@
+use crate::models::*;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Response {
+    pub html_attributions: Vec<String>,
+
+    /// The place, missing unless the status is OK.
+    pub result: Option<Place>,
+    pub status: SearchStatus,
+    pub error_message: Option<String>,
+    pub info_messages: Option<Vec<String>>,
+
+    /// Fields returned by Google that this crate does not model yet.
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+impl Response {
+    /// The place as a slice, empty when it was not found, to share the code written for search results.
+    pub fn places(&self) -> &[Place] {
+        self.result.as_slice()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Response;
+    use crate::models::SearchStatus;
+
+    #[test]
+    fn test_deserialize_details_response() {
+        let response: Response = serde_json::from_str(
+            r#"{
+                "html_attributions": [],
+                "result": {
+                    "name": "Sydney Opera House",
+                    "place_id": "ChIJ3S-JXmauEmsRUcIaWtf4MzE",
+                    "website": "https://www.sydneyoperahouse.com/",
+                    "photos": [
+                        {
+                            "height": 3024,
+                            "width": 4032,
+                            "photo_reference": "AWU5eFh",
+                            "html_attributions": []
+                        }
+                    ]
+                },
+                "status": "OK"
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(response.status, SearchStatus::Ok);
+        assert_eq!(response.places().len(), 1);
+
+        let place = response.result.unwrap();
+        assert_eq!(place.name.as_deref(), Some("Sydney Opera House"));
+        assert_eq!(place.photos.unwrap()[0].photo_reference, "AWU5eFh");
+
+        let response: Response =
+            serde_json::from_str(r#"{ "html_attributions": [], "status": "NOT_FOUND" }"#).unwrap();
+
+        assert_eq!(response.status, SearchStatus::NotFound);
+        assert!(response.places().is_empty());
+    }
+}
@@ -0,0 +1,172 @@
+use super::Column;
+use crate::models::{Error, Place};
+
+/// Quotes a field when it holds a separator, a quote or a line break, as RFC 4180 requires.
+fn escape(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_owned()
+    }
+}
+
+/// One CSV record, ending with CRLF.
+pub fn to_csv_row(fields: impl IntoIterator<Item = String>) -> String {
+    let mut line = fields
+        .into_iter()
+        .map(|field| escape(&field))
+        .collect::<Vec<_>>()
+        .join(",");
+
+    line.push_str("\r\n");
+
+    line
+}
+
+/// CSV with a header row naming the columns, then one row per place.
+pub fn to_csv(places: &[Place], columns: &[Column]) -> String {
+    let mut output = to_csv_row(columns.iter().map(Column::to_string));
+
+    for place in places {
+        output.push_str(&to_csv_row(
+            columns.iter().map(|column| column.value(place)),
+        ));
+    }
+
+    output
+}
+
+/// The length of the start of `text` holding complete records, up to the last line break outside a quoted field.
+/// What follows is a record cut short, such as by an interrupted write.
+pub fn complete_csv_len(text: &str) -> usize {
+    let mut quoted = false;
+    let mut end = 0;
+
+    // an escaped quote toggles twice, leaving `quoted` as it was.
+    for (i, byte) in text.bytes().enumerate() {
+        match byte {
+            b'"' => quoted = !quoted,
+            b'\n' if !quoted => end = i + 1,
+            _ => {}
+        }
+    }
+
+    end
+}
+
+/// Reads RFC 4180 records, the header included, with LF or CRLF line breaks and an optional byte order mark.
+/// Blank lines are skipped.
+pub fn parse_csv(text: &str) -> Result<Vec<Vec<String>>, Error> {
+    let mut records = vec![];
+    let (mut record, mut field) = (vec![], String::new());
+    let (mut quoted, mut was_quoted) = (false, false);
+    let mut chars = text.trim_start_matches('\u{feff}').chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match (quoted, c) {
+            (true, '"') if chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            (true, '"') => quoted = false,
+            (true, c) => field.push(c),
+            (false, '"') if field.is_empty() && !was_quoted => (quoted, was_quoted) = (true, true),
+            (false, ',') => {
+                record.push(std::mem::take(&mut field));
+                was_quoted = false;
+            }
+            (false, '\r') if chars.peek() == Some(&'\n') => {}
+            (false, '\n') => {
+                if !record.is_empty() || !field.is_empty() || was_quoted {
+                    record.push(std::mem::take(&mut field));
+                    records.push(std::mem::take(&mut record));
+                }
+                was_quoted = false;
+            }
+            (false, c) if was_quoted => {
+                return Err(format!(
+                    "unexpected {:?} after a quoted field on record {}",
+                    c,
+                    records.len() + 1
+                )
+                .into())
+            }
+            (false, c) => field.push(c),
+        }
+    }
+
+    if quoted {
+        return Err(format!("unterminated quoted field on record {}", records.len() + 1).into());
+    }
+
+    if !record.is_empty() || !field.is_empty() || was_quoted {
+        record.push(field);
+        records.push(record);
+    }
+
+    Ok(records)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{complete_csv_len, parse_csv, to_csv};
+    use crate::export::Column;
+    use crate::models::Place;
+
+    #[test]
+    fn test_csv_quotes_fields() {
+        let place: Place = serde_json::from_value(serde_json::json!({
+            "name": "Bills \"Surry Hills\"",
+            "formatted_address": "359 Crown St, Surry Hills NSW 2010, Australia",
+            "rating": 4.3,
+            "types": ["cafe", "food"]
+        }))
+        .unwrap();
+
+        assert_eq!(
+            to_csv(
+                &[place],
+                &[Column::Name, Column::Address, Column::Rating, Column::Types, Column::Phone]
+            ),
+            "name,address,rating,types,phone\r\n\
+             \"Bills \"\"Surry Hills\"\"\",\"359 Crown St, Surry Hills NSW 2010, Australia\",4.3,\"cafe,food\",\r\n"
+        );
+    }
+
+    #[test]
+    fn test_parse_csv() {
+        let records = parse_csv(
+            "\u{feff}name,address\r\n\
+             \"Bills \"\"Surry Hills\"\"\",\"359 Crown St,\nSurry Hills\"\r\n\
+             \r\n\
+             Gelato Messina,\n\
+             \"\",last",
+        )
+        .unwrap();
+
+        assert_eq!(
+            records,
+            vec![
+                vec!["name", "address"],
+                vec!["Bills \"Surry Hills\"", "359 Crown St,\nSurry Hills"],
+                vec!["Gelato Messina", ""],
+                vec!["", "last"],
+            ]
+        );
+
+        assert!(parse_csv("name\n\"Bills").is_err());
+        assert!(parse_csv("name\n\"Bills\" Surry Hills").is_err());
+    }
+
+    #[test]
+    fn test_complete_csv_len() {
+        let complete = "name,note\r\n\"Bills\",\"open\r\nlate, \"\"really\"\"\"\r\n";
+
+        assert_eq!(complete_csv_len(complete), complete.len());
+        assert_eq!(
+            complete_csv_len(&format!("{}Messina,\"first line\r\nsecond", complete)),
+            complete.len()
+        );
+        assert_eq!(complete_csv_len("name,note"), 0);
+    }
+}
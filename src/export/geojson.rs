@@ -0,0 +1,151 @@
+use crate::models::{Bounds, LatLng, Place};
+use crate::{nearby, place};
+use serde_json::{json, Map, Value};
+
+fn position(point: &LatLng) -> Value {
+    json!([point.lng, point.lat])
+}
+
+/// The bounds as a Polygon whose exterior ring runs counterclockwise, as RFC 7946 requires.
+fn polygon(bounds: &Bounds) -> Value {
+    let (sw, ne) = (bounds.southwest, bounds.northeast);
+
+    json!({
+        "type": "Polygon",
+        "coordinates": [[
+            position(&sw),
+            position(&LatLng::new(sw.lat, ne.lng)),
+            position(&ne),
+            position(&LatLng::new(ne.lat, sw.lng)),
+            position(&sw),
+        ]]
+    })
+}
+
+/// Every field of the place but its geometry, leaving out the ones it lacks.
+///
+/// The place goes through its JSON text, where the f32 rating is written as it was read,
+/// since `serde_json::to_value` would widen 4.1 to 4.099999904632568.
+fn properties(place: &Place) -> Map<String, Value> {
+    let value = serde_json::to_string(place).and_then(|text| serde_json::from_str(&text));
+
+    match value {
+        Ok(Value::Object(fields)) => fields
+            .into_iter()
+            .filter(|(key, value)| key != "geometry" && !value.is_null())
+            .collect(),
+        _ => Map::new(),
+    }
+}
+
+/// A FeatureCollection with a Point feature per place, at its `geometry.location`, and the place's other fields as properties.
+/// Places without a location get a null geometry.
+///
+/// With `viewport`, each place's viewport is added as a Polygon feature of its own,
+/// with only the place_id, name and `"viewport": true` as properties, so that it can be styled as a separate layer.
+pub fn to_geojson(places: &[Place], viewport: bool) -> Value {
+    let mut features = vec![];
+
+    for place in places {
+        features.push(json!({
+            "type": "Feature",
+            "geometry": place.location().map(|location| json!({
+                "type": "Point",
+                "coordinates": position(location),
+            })),
+            "properties": properties(place),
+        }));
+
+        if let (true, Some(geometry)) = (viewport, &place.geometry) {
+            features.push(json!({
+                "type": "Feature",
+                "geometry": polygon(&geometry.viewport),
+                "properties": {
+                    "place_id": place.place_id,
+                    "name": place.name,
+                    "viewport": true,
+                },
+            }));
+        }
+    }
+
+    json!({
+        "type": "FeatureCollection",
+        "features": features,
+    })
+}
+
+impl nearby::Response {
+    pub fn to_geojson(&self, viewport: bool) -> Value {
+        to_geojson(&self.results, viewport)
+    }
+}
+
+impl place::Response {
+    pub fn to_geojson(&self, viewport: bool) -> Value {
+        to_geojson(&self.candidates, viewport)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::to_geojson;
+    use crate::models::MultiPolygon;
+    use crate::nearby;
+    use serde_json::json;
+
+    fn response() -> nearby::Response {
+        serde_json::from_value(json!({
+            "html_attributions": [],
+            "status": "OK",
+            "results": [
+                {
+                    "place_id": "ChIJi6C1MxquEmsR9-c-3O48ykI",
+                    "name": "Cruise Bar",
+                    "rating": 4.1,
+                    "geometry": {
+                        "location": { "lat": -33.8587323, "lng": 151.2100055 },
+                        "viewport": {
+                            "northeast": { "lat": -33.857, "lng": 151.211 },
+                            "southwest": { "lat": -33.860, "lng": 151.208 }
+                        }
+                    }
+                },
+                { "place_id": "nowhere", "name": "No location" }
+            ]
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_geojson_points() {
+        let collection = response().to_geojson(false);
+        let features = collection["features"].as_array().unwrap();
+
+        assert_eq!(collection["type"], "FeatureCollection");
+        assert_eq!(features.len(), 2);
+        assert_eq!(
+            features[0]["geometry"],
+            json!({ "type": "Point", "coordinates": [151.2100055, -33.8587323] })
+        );
+        assert_eq!(
+            features[0]["properties"].to_string(),
+            r#"{"name":"Cruise Bar","place_id":"ChIJi6C1MxquEmsR9-c-3O48ykI","rating":4.1}"#
+        );
+        assert!(features[1]["geometry"].is_null());
+    }
+
+    #[test]
+    fn test_geojson_viewport() {
+        let collection = to_geojson(&response().results, true);
+        let features = collection["features"].as_array().unwrap();
+
+        assert_eq!(features.len(), 3);
+        assert_eq!(features[1]["properties"]["viewport"], true);
+
+        // the polygon reads back, and holds the place.
+        let viewport = MultiPolygon::from_geojson_value(&features[1]["geometry"]).unwrap();
+        let location = response().results[0].location().copied().unwrap();
+        assert!(viewport.contains(&location));
+    }
+}
@@ -0,0 +1,79 @@
+use super::escape_xml;
+use crate::models::Place;
+use crate::resolve::address_of;
+
+/// A GPX 1.1 document with a waypoint per place that has a location.
+pub fn to_gpx(places: &[Place]) -> String {
+    let mut waypoints = String::new();
+
+    for place in places {
+        let location = match place.location() {
+            Some(location) => location,
+            None => continue,
+        };
+
+        waypoints.push_str(&format!(
+            "  <wpt lat=\"{}\" lon=\"{}\">\n",
+            location.lat, location.lng
+        ));
+
+        if let Some(name) = &place.name {
+            waypoints.push_str(&format!("    <name>{}</name>\n", escape_xml(name)));
+        }
+
+        if let Some(address) = address_of(place) {
+            waypoints.push_str(&format!("    <desc>{}</desc>\n", escape_xml(address)));
+        }
+
+        if let Some(kind) = place.types.as_ref().and_then(|types| types.first()) {
+            waypoints.push_str(&format!("    <type>{}</type>\n", escape_xml(kind)));
+        }
+
+        waypoints.push_str("  </wpt>\n");
+    }
+
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <gpx version=\"1.1\" creator=\"google-place-api\" xmlns=\"http://www.topografix.com/GPX/1/1\">\n\
+         {}</gpx>\n",
+        waypoints
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::to_gpx;
+    use crate::models::Place;
+
+    #[test]
+    fn test_gpx_waypoints() {
+        let places: Vec<Place> = serde_json::from_value(serde_json::json!([
+            {
+                "name": "Café <Sydney>",
+                "formatted_address": "2 George St, Sydney",
+                "types": ["cafe"],
+                "geometry": {
+                    "location": { "lat": -33.86, "lng": 151.21 },
+                    "viewport": {
+                        "northeast": { "lat": -33.86, "lng": 151.21 },
+                        "southwest": { "lat": -33.86, "lng": 151.21 }
+                    }
+                }
+            },
+            { "name": "Nowhere" }
+        ]))
+        .unwrap();
+
+        assert_eq!(
+            to_gpx(&places),
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+             <gpx version=\"1.1\" creator=\"google-place-api\" xmlns=\"http://www.topografix.com/GPX/1/1\">\n\
+             \x20 <wpt lat=\"-33.86\" lon=\"151.21\">\n\
+             \x20   <name>Café &lt;Sydney&gt;</name>\n\
+             \x20   <desc>2 George St, Sydney</desc>\n\
+             \x20   <type>cafe</type>\n\
+             \x20 </wpt>\n\
+             </gpx>\n"
+        );
+    }
+}
@@ -0,0 +1,139 @@
+use super::escape_xml;
+use crate::models::Place;
+use crate::resolve::address_of;
+
+/// The address and rating of a place, one per line.
+fn description(place: &Place) -> String {
+    let mut lines = vec![];
+
+    if let Some(address) = address_of(place) {
+        lines.push(address.to_owned());
+    }
+
+    match (place.rating, place.user_ratings_total) {
+        (Some(rating), Some(total)) => {
+            lines.push(format!("Rating: {} ({} reviews)", rating, total))
+        }
+        (Some(rating), None) => lines.push(format!("Rating: {}", rating)),
+        _ => {}
+    }
+
+    lines.join("\n")
+}
+
+fn placemark(place: &Place, indent: &str) -> Option<String> {
+    let location = place.location()?;
+
+    Some(format!(
+        "{indent}<Placemark>\n\
+         {indent}  <name>{}</name>\n\
+         {indent}  <description>{}</description>\n\
+         {indent}  <Point><coordinates>{},{}</coordinates></Point>\n\
+         {indent}</Placemark>\n",
+        escape_xml(place.name.as_deref().unwrap_or_default()),
+        escape_xml(&description(place)),
+        location.lng,
+        location.lat,
+        indent = indent,
+    ))
+}
+
+/// A KML document with a placemark per place that has a location.
+///
+/// With `folders`, placemarks are grouped in a folder per place type, named after the first of the place's `types`,
+/// in the order types are first seen. Places without types go to an "other" folder.
+pub fn to_kml(places: &[Place], folders: bool) -> String {
+    let mut body = String::new();
+
+    if folders {
+        let mut groups: Vec<(&str, Vec<&Place>)> = vec![];
+
+        for place in places {
+            let kind = place
+                .types
+                .as_ref()
+                .and_then(|types| types.first())
+                .map_or("other", String::as_str);
+
+            match groups.iter_mut().find(|(name, _)| *name == kind) {
+                Some((_, members)) => members.push(place),
+                None => groups.push((kind, vec![place])),
+            }
+        }
+
+        for (kind, members) in groups {
+            body.push_str(&format!(
+                "    <Folder>\n      <name>{}</name>\n",
+                escape_xml(kind)
+            ));
+            body.extend(
+                members
+                    .iter()
+                    .filter_map(|place| placemark(place, "      ")),
+            );
+            body.push_str("    </Folder>\n");
+        }
+    } else {
+        body.extend(places.iter().filter_map(|place| placemark(place, "    ")));
+    }
+
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <kml xmlns=\"http://www.opengis.net/kml/2.2\">\n  \
+         <Document>\n{}  </Document>\n\
+         </kml>\n",
+        body
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::to_kml;
+    use crate::models::Place;
+
+    fn places() -> Vec<Place> {
+        serde_json::from_value(serde_json::json!([
+            {
+                "name": "Fish & Chips",
+                "vicinity": "1 Wharf Rd, Sydney",
+                "rating": 4.2,
+                "user_ratings_total": 87,
+                "types": ["restaurant", "food"],
+                "geometry": {
+                    "location": { "lat": -33.86, "lng": 151.21 },
+                    "viewport": {
+                        "northeast": { "lat": -33.86, "lng": 151.21 },
+                        "southwest": { "lat": -33.86, "lng": 151.21 }
+                    }
+                }
+            },
+            { "name": "Nowhere", "types": ["bar"] }
+        ]))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_kml_placemarks() {
+        assert_eq!(
+            to_kml(&places(), false),
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+             <kml xmlns=\"http://www.opengis.net/kml/2.2\">\n\
+             \x20 <Document>\n\
+             \x20   <Placemark>\n\
+             \x20     <name>Fish &amp; Chips</name>\n\
+             \x20     <description>1 Wharf Rd, Sydney\nRating: 4.2 (87 reviews)</description>\n\
+             \x20     <Point><coordinates>151.21,-33.86</coordinates></Point>\n\
+             \x20   </Placemark>\n\
+             \x20 </Document>\n\
+             </kml>\n"
+        );
+    }
+
+    #[test]
+    fn test_kml_folders() {
+        let kml = to_kml(&places(), true);
+
+        assert!(kml.contains("    <Folder>\n      <name>restaurant</name>\n      <Placemark>"));
+        assert!(kml.contains("<name>bar</name>\n    </Folder>"));
+    }
+}
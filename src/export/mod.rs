@@ -0,0 +1,105 @@
+//! Conversions of search results into formats other tools can read.
+
+mod csv;
+mod geojson;
+mod gpx;
+mod kml;
+mod table;
+
+pub use self::csv::*;
+pub use geojson::*;
+pub use gpx::*;
+pub use kml::*;
+pub use table::*;
+
+use crate::models::{Error, Place};
+use crate::resolve::{address_of, phone_of};
+use clap::ArgEnum;
+use strum_macros::Display;
+
+/// A property of a place, as a column of the CSV and table outputs.
+#[derive(Clone, Copy, ArgEnum, Display, Debug, PartialEq, Eq)]
+#[strum(serialize_all = "snake_case")]
+pub enum Column {
+    PlaceId,
+    Name,
+    Address,
+    Lat,
+    Lng,
+    Rating,
+    UserRatingsTotal,
+    PriceLevel,
+    BusinessStatus,
+    OpenNow,
+    Phone,
+    Website,
+    Types,
+}
+
+impl Column {
+    /// The columns used when none are selected.
+    pub const DEFAULT: [Column; 6] = [
+        Column::Name,
+        Column::Address,
+        Column::Lat,
+        Column::Lng,
+        Column::Rating,
+        Column::PlaceId,
+    ];
+
+    /// The value of this column for `place`, empty when the place lacks it.
+    pub fn value(&self, place: &Place) -> String {
+        fn text<T: ToString>(value: Option<T>) -> String {
+            value.map(|value| value.to_string()).unwrap_or_default()
+        }
+
+        match self {
+            Column::PlaceId => text(place.place_id.as_ref()),
+            Column::Name => text(place.name.as_ref()),
+            Column::Address => text(address_of(place)),
+            Column::Lat => text(place.location().map(|location| location.lat)),
+            Column::Lng => text(place.location().map(|location| location.lng)),
+            Column::Rating => text(place.rating),
+            Column::UserRatingsTotal => text(place.user_ratings_total),
+            Column::PriceLevel => text(place.price_level),
+            Column::BusinessStatus => text(
+                place
+                    .business_status
+                    .as_ref()
+                    .and_then(|status| serde_json::to_value(status).ok())
+                    .as_ref()
+                    .and_then(|status| status.as_str()),
+            ),
+            Column::OpenNow => text(
+                place
+                    .opening_hours
+                    .as_ref()
+                    .and_then(|hours| hours.open_now),
+            ),
+            Column::Phone => text(phone_of(place)),
+            Column::Website => text(place.website.as_ref()),
+            Column::Types => text(place.types.as_ref().map(|types| types.join(","))),
+        }
+    }
+}
+
+/// Newline-delimited JSON, one place per line.
+pub fn to_ndjson(places: &[Place]) -> Result<String, Error> {
+    let mut output = String::new();
+
+    for place in places {
+        output.push_str(&serde_json::to_string(place)?);
+        output.push('\n');
+    }
+
+    Ok(output)
+}
+
+/// Escapes the characters XML reserves, for text content and attribute values alike.
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
@@ -0,0 +1,92 @@
+use super::Column;
+use crate::models::Place;
+
+/// Cells longer than this are cut, so that a table stays readable in a terminal.
+const MAX_WIDTH: usize = 40;
+
+fn truncate(cell: String) -> String {
+    if cell.chars().count() <= MAX_WIDTH {
+        return cell;
+    }
+
+    let mut cut = cell.chars().take(MAX_WIDTH - 1).collect::<String>();
+    cut.push('…');
+
+    cut
+}
+
+/// A table aligned with spaces, with a header and a rule under it.
+pub fn to_table(places: &[Place], columns: &[Column]) -> String {
+    let header = columns
+        .iter()
+        .map(|column| column.to_string().to_uppercase())
+        .collect::<Vec<_>>();
+
+    let rows = places
+        .iter()
+        .map(|place| {
+            columns
+                .iter()
+                .map(|column| truncate(column.value(place)))
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    let widths = (0..columns.len())
+        .map(|i| {
+            rows.iter()
+                .chain(std::iter::once(&header))
+                .map(|row| row[i].chars().count())
+                .max()
+                .unwrap_or(0)
+        })
+        .collect::<Vec<_>>();
+
+    let line = |cells: &[String]| {
+        let padded = cells
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{:width$}", cell, width = width))
+            .collect::<Vec<_>>();
+
+        format!("{}\n", padded.join("  ").trim_end())
+    };
+
+    let rule = widths
+        .iter()
+        .map(|width| "-".repeat(*width))
+        .collect::<Vec<_>>();
+
+    let mut output = line(&header);
+    output.push_str(&line(&rule));
+
+    for row in &rows {
+        output.push_str(&line(row));
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::to_table;
+    use crate::export::Column;
+    use crate::models::Place;
+
+    #[test]
+    fn test_table_aligns_columns() {
+        let places: Vec<Place> = serde_json::from_value(serde_json::json!([
+            { "name": "Cruise Bar", "rating": 4.0 },
+            { "name": "Sydney Harbour Marriott Hotel at Circular Quay, a very long name", "rating": 4.5 },
+        ]))
+        .unwrap();
+
+        assert_eq!(
+            to_table(&places, &[Column::Name, Column::Rating]),
+            "NAME                                      RATING\n\
+             ----------------------------------------  ------\n\
+             Cruise Bar                                4\n\
+             Sydney Harbour Marriott Hotel at Circul…  4.5\n"
+        );
+    }
+}
@@ -1,18 +1,111 @@
-use reqwest;
+use reqwest::header::HeaderMap;
 use reqwest::Url;
 use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::time::{Duration, Instant};
 
 type Error = Box<dyn std::error::Error>;
 
+/// Hides the API key from the url that reqwest puts in its error messages,
+/// which end up in logs and batch outputs.
+pub(crate) fn redact_key(error: reqwest::Error) -> Error {
+    let url = match error.url() {
+        Some(url) => url,
+        None => return error.into(),
+    };
+
+    let pairs = url
+        .query_pairs()
+        .map(|(name, value)| match name.as_ref() {
+            "key" => (name.into_owned(), "REDACTED".to_owned()),
+            _ => (name.into_owned(), value.into_owned()),
+        })
+        .collect::<Vec<_>>();
+
+    let mut redacted = url.clone();
+    redacted.query_pairs_mut().clear().extend_pairs(pairs);
+
+    error
+        .to_string()
+        .replace(url.as_str(), redacted.as_str())
+        .into()
+}
+
 pub async fn fetch<T>(url: &str, params: &[(impl AsRef<str>, impl AsRef<str>)]) -> Result<T, Error>
 where
     T: DeserializeOwned,
 {
     let url = Url::parse_with_params(url, params)?;
 
-    let res = reqwest::get(url).await?;
+    let res = reqwest::get(url).await.map_err(redact_key)?;
 
-    let output = res.json().await?;
+    let output = res.json().await.map_err(redact_key)?;
 
     Ok(output)
 }
+
+/// A typed response together with everything needed to debug it.
+#[derive(Debug)]
+pub struct RawResponse<T> {
+    /// The typed response.
+    pub response: T,
+
+    /// The untouched JSON body, including fields the typed response does not know about.
+    pub body: Value,
+
+    /// The HTTP headers returned by Google.
+    pub headers: HeaderMap,
+
+    /// Time elapsed between sending the request and receiving the whole body.
+    pub latency: Duration,
+}
+
+pub async fn fetch_raw<T>(
+    url: &str,
+    params: &[(impl AsRef<str>, impl AsRef<str>)],
+) -> Result<RawResponse<T>, Error>
+where
+    T: DeserializeOwned,
+{
+    let url = Url::parse_with_params(url, params)?;
+
+    let start = Instant::now();
+
+    let res = reqwest::get(url).await.map_err(redact_key)?;
+
+    let headers = res.headers().clone();
+
+    let body: Value = res.json().await.map_err(redact_key)?;
+
+    let latency = start.elapsed();
+
+    let response = serde_json::from_value(body.clone())?;
+
+    Ok(RawResponse {
+        response,
+        body,
+        headers,
+        latency,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::fetch;
+    use serde_json::Value;
+
+    #[tokio::test]
+    async fn test_errors_hide_the_key() {
+        // the .invalid top-level domain never resolves.
+        let error = fetch::<Value>(
+            "http://places.invalid/json",
+            &[("key", "secret key"), ("input", "museum")],
+        )
+        .await
+        .unwrap_err()
+        .to_string();
+
+        assert!(!error.contains("secret"), "{}", error);
+        assert!(error.contains("key=REDACTED&input=museum"), "{}", error);
+    }
+}
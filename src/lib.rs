@@ -1,12 +1,28 @@
 mod fetch;
-mod models;
+
+pub mod autocomplete;
+pub mod batch;
+pub mod cache;
+pub mod crawl;
+pub mod details;
+pub mod export;
+pub mod models;
 
 pub mod nearby;
+pub mod photo;
 pub mod place;
-pub use fetch::fetch;
+pub mod polyline;
+pub mod resolve;
+pub mod route;
+pub mod sweep;
+pub mod throttle;
+
+#[cfg(test)]
+mod testing;
+pub use fetch::{fetch, fetch_raw, RawResponse};
 
 use async_trait::async_trait;
-use models::{Error, LatLng};
+use models::{Bounds, Error, LatLng, MultiPolygon};
 use serde::de::DeserializeOwned;
 
 pub trait SendUrl {
@@ -29,7 +45,23 @@ where
     R: DeserializeOwned,
 {
     async fn send(&self) -> Result<R, Error> {
-        Ok(fetch(&self.get_url(), &self.get_params()).await?)
+        Ok(fetch(self.get_url(), &self.get_params()).await?)
+    }
+}
+
+#[async_trait]
+pub trait SendRaw<Response, Error> {
+    async fn send_raw(&self) -> Result<RawResponse<Response>, Error>;
+}
+
+#[async_trait]
+impl<T, R> SendRaw<R, Error> for T
+where
+    T: SearchParams + SendUrl + std::marker::Sync,
+    R: DeserializeOwned,
+{
+    async fn send_raw(&self) -> Result<RawResponse<R>, Error> {
+        Ok(fetch_raw(self.get_url(), &self.get_params()).await?)
     }
 }
 
@@ -65,6 +97,69 @@ impl Client {
             ..Default::default()
         }
     }
+
+    /// Predicts the places a partly typed text may stand for, as the user types it.
+    pub fn autocomplete(&self, input: impl Into<String>) -> autocomplete::Request {
+        autocomplete::Request {
+            url: "https://maps.googleapis.com/maps/api/place/autocomplete/json",
+            token: self.token.clone(),
+            input: input.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Looks up the full record of a place by its place_id.
+    pub fn details(&self, place_id: impl Into<String>) -> details::Request {
+        details::Request {
+            url: "https://maps.googleapis.com/maps/api/place/details/json",
+            token: self.token.clone(),
+            place_id: place_id.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Downloads a photo by the photo_reference of a search or details result, at most `max_width` pixels wide.
+    pub fn photo(&self, photo_reference: impl Into<String>, max_width: u32) -> photo::Request {
+        photo::Request {
+            url: "https://maps.googleapis.com/maps/api/place/photo",
+            token: self.token.clone(),
+            photo_reference: photo_reference.into(),
+            max_width: Some(max_width),
+            max_height: None,
+        }
+    }
+
+    /// Sends many requests, such as `find` or `nearby` ones, a few at a time.
+    pub fn batch<I: IntoIterator>(&self, requests: I) -> batch::Batch<I::IntoIter> {
+        batch::Batch::new(requests.into_iter())
+    }
+
+    pub fn sweep(&self, area: Bounds) -> sweep::Sweep {
+        sweep::Sweep::new(self.nearby(0.0, 0.0), sweep::Region::Bounds(area))
+    }
+
+    pub fn sweep_around(&self, center: LatLng, radius: f64) -> sweep::Sweep {
+        let circle = sweep::Circle { center, radius };
+
+        sweep::Sweep::new(self.nearby(0.0, 0.0), sweep::Region::Circle(circle))
+    }
+
+    pub fn sweep_polygon(&self, polygon: impl Into<MultiPolygon>) -> sweep::Sweep {
+        sweep::Sweep::new(
+            self.nearby(0.0, 0.0),
+            sweep::Region::Polygon(polygon.into()),
+        )
+    }
+
+    /// Searches for places along a route given as a list of points.
+    pub fn search_route(&self, path: Vec<LatLng>) -> route::RouteSearch {
+        route::RouteSearch::new(self.nearby(0.0, 0.0), path)
+    }
+
+    /// Searches for places along a route given as an encoded polyline, such as a Directions API overview_polyline.
+    pub fn search_polyline(&self, encoded: &str) -> Result<route::RouteSearch, Error> {
+        Ok(self.search_route(polyline::decode(encoded)?))
+    }
 }
 
 #[cfg(test)]
@@ -72,6 +167,7 @@ mod tests {
     use std::ops::Deref;
 
     use super::{Client, SearchParams};
+    use crate::details::Field;
     use crate::models::LatLng;
 
     #[test]
@@ -95,7 +191,7 @@ mod tests {
         T: ToString,
     {
         params
-            .into_iter()
+            .iter()
             .map(|(a, b)| format!("({},{})", a.to_string(), b.to_string()))
             .collect::<Vec<_>>()
             .join(",")
@@ -146,4 +242,69 @@ mod tests {
             format_search_params(&right)
         )
     }
+
+    #[test]
+    fn test_details_and_photo() {
+        let client = Client::new("hello kirby");
+
+        let mut request = client.details("ChIJ3S-JXmauEmsRUcIaWtf4MzE");
+        request
+            .add_fields(vec![Field::Name, Field::Website])
+            .set_session_token("session");
+
+        let left = vec![
+            ("key", "hello kirby"),
+            ("place_id", "ChIJ3S-JXmauEmsRUcIaWtf4MzE"),
+            ("fields", "name,website"),
+            ("sessiontoken", "session"),
+        ];
+
+        assert_eq!(
+            format_search_params(&left),
+            format_search_params(&request.get_params())
+        );
+
+        let mut request = client.photo("AWU5eFh", 800);
+        request.set_max_height(600);
+
+        let left = vec![
+            ("key", "hello kirby"),
+            ("photo_reference", "AWU5eFh"),
+            ("maxwidth", "800"),
+            ("maxheight", "600"),
+        ];
+
+        assert_eq!(
+            format_search_params(&left),
+            format_search_params(&request.get_params())
+        );
+    }
+
+    #[test]
+    fn test_autocomplete_request() {
+        let mut request = Client::new("hello kirby").autocomplete("opera");
+        request
+            .set_session_token("session")
+            .set_location(LatLng::new(-33.86, 151.21), 5000)
+            .add_country("au")
+            .add_country("nz");
+
+        let left = vec![
+            ("key", "hello kirby"),
+            ("input", "opera"),
+            ("sessiontoken", "session"),
+            ("location", "-33.86,151.21"),
+            ("radius", "5000"),
+            ("components", "country:au|country:nz"),
+        ];
+
+        assert_eq!(
+            format_search_params(&left),
+            format_search_params(&request.get_params())
+        );
+
+        let token = crate::autocomplete::new_session_token();
+        assert_eq!(token.len(), 36);
+        assert_ne!(token, crate::autocomplete::new_session_token());
+    }
 }
@@ -1,9 +1,34 @@
+mod config;
+mod repl;
+mod rows;
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
 use std::process;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
 
-use clap::{Parser, Subcommand};
+use clap::{ArgEnum, ArgGroup, Parser, Subcommand};
+use crossterm::event::{self, Event};
+use crossterm::terminal;
+use crossterm::tty::IsTty;
+use futures::StreamExt;
+use google_place_api::autocomplete;
+use google_place_api::cache::Cache;
+use google_place_api::details;
+use google_place_api::export::{self, Column};
+use google_place_api::models::{LatLng, ParseLatLngError, Place, SearchStatus};
 use google_place_api::nearby;
+use google_place_api::photo::{self, Photo};
 use google_place_api::place;
-use google_place_api::{Client, Send};
+use google_place_api::throttle::RateLimiter;
+use google_place_api::{fetch, Client, SearchParams, SendUrl};
+use rows::{Format, Output, Rows, Template};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
 
 type Error = Box<dyn std::error::Error>;
 
@@ -12,12 +37,332 @@ type Error = Box<dyn std::error::Error>;
 struct Cli {
     #[clap(subcommand)]
     command: Commands,
+
+    #[clap(flatten)]
+    key: KeyArgs,
+
+    #[clap(flatten)]
+    output: OutputArgs,
+
+    /// the profile of the config file to take defaults from, its `default_profile` otherwise.
+    #[clap(long, global = true)]
+    profile: Option<String>,
+
+    /// the language in which to return results.
+    #[clap(long, global = true)]
+    language: Option<String>,
+}
+
+/// The API key is taken from the first of --token, --key-file, the `key` of the profile given with --profile,
+/// GOOGLE_PLACES_API_KEY, the `key` of the default profile and the `key` of the config file.
+#[derive(Parser)]
+struct KeyArgs {
+    /// google api key, prefer the other sources to keep it out of the shell history.
+    #[clap(long, global = true)]
+    token: Option<String>,
+
+    /// a file holding the google api key.
+    #[clap(long, global = true)]
+    key_file: Option<PathBuf>,
+}
+
+/// Reads a value of an argument enum from the config file, accepting snake_case names too.
+fn parse_enum<T: ArgEnum>(value: &str, setting: &str) -> Result<T, Error> {
+    T::from_str(&value.replace('_', "-"), true)
+        .map_err(|_| format!("invalid {} {:?} in the config profile", setting, value).into())
+}
+
+/// What every command shares: the client, and the defaults and limits of the selected profile.
+struct Session {
+    client: Client,
+    language: Option<String>,
+    fields: Vec<place::Field>,
+    limiter: Option<RateLimiter>,
+    cache: Option<Cache>,
+}
+
+impl Session {
+    /// Loads the config file and picks the API key and the profile, letting command-line options win.
+    fn open(cli: &mut Cli) -> Result<Session, Error> {
+        let path = config::config_path();
+        let config = match &path {
+            Some(path) => config::Config::load(path)?,
+            None => None,
+        }
+        .unwrap_or_default();
+
+        let selected = config.profile(cli.profile.as_deref())?;
+        let profile = selected
+            .map(|(_, profile)| profile.clone())
+            .unwrap_or_default();
+
+        let variable = std::env::var(config::KEY_VARIABLE).ok();
+        let key = config::KeySources {
+            token: cli.key.token.as_deref(),
+            key_file: cli.key.key_file.as_deref(),
+            profile: selected.map(|(name, profile)| (name, profile.key.as_deref())),
+            explicit_profile: cli.profile.is_some(),
+            variable: variable.as_deref(),
+            config: path.as_deref().map(|path| (path, config.key.as_deref())),
+        }
+        .resolve()?;
+
+        if let (None, Some(output)) = (cli.output.output, &profile.output) {
+            cli.output.output = Some(parse_enum(output, "output")?);
+        }
+
+        Ok(Session {
+            client: Client::new(key),
+            language: cli.language.clone().or(profile.language),
+            fields: profile
+                .fields
+                .iter()
+                .map(|field| parse_enum(field, "field"))
+                .collect::<Result<_, _>>()?,
+            limiter: profile.rate_limit.map(RateLimiter::new),
+            cache: profile.cache.map(|cache| cache.cache()).transpose()?,
+        })
+    }
+
+    /// Sends a request, served from the cache when possible and spaced out by the rate limit otherwise.
+    async fn send<T, R>(&self, request: &T) -> Result<R, Error>
+    where
+        T: SearchParams + SendUrl,
+        R: DeserializeOwned,
+    {
+        let (url, params) = (request.get_url(), request.get_params());
+
+        if let Some(body) = self
+            .cache
+            .as_ref()
+            .and_then(|cache| cache.get(url, &params))
+        {
+            return Ok(serde_json::from_value(body)?);
+        }
+
+        if let Some(limiter) = &self.limiter {
+            limiter.acquire().await;
+        }
+
+        let body: Value = fetch(url, &params).await?;
+
+        // errors such as OVER_QUERY_LIMIT are worth retrying later rather than caching.
+        if let (Some(cache), Some("OK" | "ZERO_RESULTS")) = (&self.cache, body["status"].as_str()) {
+            cache.put(url, &params, &body)?;
+        }
+
+        Ok(serde_json::from_value(body)?)
+    }
+
+    /// Place Details of `place_id`, with the profile's fields when `fields` is empty,
+    /// ending the autocomplete session of `session_token` if any.
+    async fn details(
+        &self,
+        place_id: &str,
+        fields: Vec<details::Field>,
+        session_token: Option<&str>,
+    ) -> Result<details::Response, Error> {
+        let mut request = self.client.details(place_id);
+
+        if let Some(session_token) = session_token {
+            request.set_session_token(session_token);
+        }
+
+        if fields.is_empty() {
+            // Find Place fields are Place Details fields under the same names.
+            request.add_fields(
+                self.fields
+                    .iter()
+                    .map(|field| parse_enum(&field.to_string(), "field"))
+                    .collect::<Result<_, _>>()?,
+            );
+        } else {
+            request.add_fields(fields);
+        }
+
+        if let Some(language) = &self.language {
+            request.set_language(language);
+        }
+
+        self.send(&request).await
+    }
+
+    /// Sends a request spaced out by the rate limit, never from the cache, such as a page of results
+    /// whose next_page_token must be fresh.
+    async fn send_uncached<R: DeserializeOwned>(
+        &self,
+        url: &'static str,
+        params: Vec<(String, String)>,
+    ) -> Result<R, Error> {
+        if let Some(limiter) = &self.limiter {
+            limiter.acquire().await;
+        }
+
+        fetch(url, &params).await
+    }
+
+    /// Downloads a photo, spaced out by the rate limit. Images are never cached.
+    async fn download(&self, request: &photo::Request) -> Result<Photo, Error> {
+        if let Some(limiter) = &self.limiter {
+            limiter.acquire().await;
+        }
+
+        request.download().await
+    }
+
+    /// The coordinates of a location, looking an address up with Find Place.
+    async fn locate(&self, location: &Location) -> Result<LatLng, Error> {
+        let address = match location {
+            Location::Point(point) => return Ok(*point),
+            Location::Address(address) => address,
+        };
+
+        let mut request = self.client.find(address, "textquery");
+        request.add_fields(vec![
+            place::Field::Geometry,
+            place::Field::Name,
+            place::Field::FormattedAddress,
+        ]);
+
+        if let Some(language) = &self.language {
+            request.set_language(language);
+        }
+
+        let response: place::Response = self.send(&request).await?;
+
+        if !matches!(
+            response.status,
+            SearchStatus::Ok | SearchStatus::ZeroResults
+        ) {
+            return Err(format!(
+                "cannot look up {:?}: {:?} {}",
+                address,
+                response.status,
+                response.error_message.unwrap_or_default()
+            )
+            .into());
+        }
+
+        let found = response
+            .candidates
+            .iter()
+            .find_map(|candidate| candidate.location().map(|point| (candidate, *point)));
+
+        match found {
+            Some((candidate, point)) => {
+                eprintln!(
+                    "{} is {}, at {}",
+                    address,
+                    candidate
+                        .formatted_address
+                        .as_deref()
+                        .or(candidate.name.as_deref())
+                        .unwrap_or("a place"),
+                    point
+                );
+
+                Ok(point)
+            }
+            None => Err(format!("no place found for {:?}", address).into()),
+        }
+    }
+}
+
+/// A point given on the command line, as coordinates or as an address to look up.
+#[derive(Debug, Clone)]
+enum Location {
+    Point(LatLng),
+    Address(String),
+}
+
+impl FromStr for Location {
+    type Err = ParseLatLngError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        // hemisphere letters and plus codes aside, a word such as "Sydney" makes an address.
+        let has_word = text
+            .split(|c: char| !c.is_alphabetic())
+            .any(|word| word.chars().count() > 1);
+
+        match text.parse() {
+            Ok(point) => Ok(Location::Point(point)),
+            Err(_) if has_word => Ok(Location::Address(text.trim().to_owned())),
+            Err(error) => Err(error),
+        }
+    }
+}
+
+#[derive(Clone, Copy, ArgEnum)]
+enum OutputFormat {
+    /// the whole response as indented JSON
+    Pretty,
+    /// the whole response as JSON on a single line
+    Json,
+    /// one place per line, as JSON
+    Ndjson,
+    Csv,
+    Table,
+    /// a FeatureCollection with a point per place
+    Geojson,
+    /// placemarks for Google Earth and most GPS apps
+    Kml,
+    /// waypoints for GPS apps
+    Gpx,
+}
+
+#[derive(Parser)]
+struct OutputArgs {
+    /// how to print the response, pretty unless the profile says otherwise.
+    #[clap(long, global = true, arg_enum)]
+    output: Option<OutputFormat>,
+
+    /// the place properties printed by the csv and table outputs.
+    #[clap(long, global = true, arg_enum, use_delimiter = true)]
+    columns: Vec<Column>,
+
+    /// add each place's viewport as a polygon to the geojson output.
+    #[clap(long, global = true)]
+    viewports: bool,
+
+    /// group the kml placemarks in a folder per place type.
+    #[clap(long, global = true)]
+    folders: bool,
+}
+
+impl OutputArgs {
+    fn print(&self, response: &impl Serialize, places: &[Place]) -> Result<(), Error> {
+        let columns = if self.columns.is_empty() {
+            &Column::DEFAULT[..]
+        } else {
+            &self.columns[..]
+        };
+
+        match self.output.unwrap_or(OutputFormat::Pretty) {
+            OutputFormat::Pretty => println!("{}", serde_json::to_string_pretty(response)?),
+            OutputFormat::Json => println!("{}", serde_json::to_string(response)?),
+            OutputFormat::Ndjson => print!("{}", export::to_ndjson(places)?),
+            OutputFormat::Csv => print!("{}", export::to_csv(places, columns)),
+            OutputFormat::Table => print!("{}", export::to_table(places, columns)),
+            OutputFormat::Geojson => println!(
+                "{}",
+                serde_json::to_string_pretty(&export::to_geojson(places, self.viewports))?
+            ),
+            OutputFormat::Kml => print!("{}", export::to_kml(places, self.folders)),
+            OutputFormat::Gpx => print!("{}", export::to_gpx(places)),
+        }
+
+        Ok(())
+    }
 }
 
 #[derive(Subcommand)]
 enum Commands {
     Find(FindArgs),
     Nearby(NearbyArgs),
+    Details(DetailsArgs),
+    Photo(PhotoArgs),
+    Batch(BatchArgs),
+    Repl(ReplArgs),
 }
 
 #[derive(Parser)]
@@ -26,29 +371,33 @@ struct FindArgs {
     #[clap(long)]
     input: String,
 
-    /// google api key
-    #[clap(long)]
-    token: String,
-
     /// the type of input.
     #[clap(long, arg_enum)]
     input_type: place::InputType,
 
-    /// specify a list of place data types to return
+    /// specify a list of place data types to return, the profile's `fields` by default.
     #[clap(long, arg_enum, multiple_values = true)]
     fields: Vec<place::Field>,
 }
 
-async fn find(config: FindArgs) -> Result<(), Error> {
-    let output: place::Response = Client::new(config.token)
-        .find(config.input, config.input_type.to_string())
-        .add_fields(config.fields)
-        .send()
-        .await?;
+async fn find(session: &Session, config: FindArgs, output: &OutputArgs) -> Result<(), Error> {
+    let mut request = session
+        .client
+        .find(config.input, config.input_type.to_string());
 
-    println!("{:#?}", output);
+    if config.fields.is_empty() {
+        request.add_fields(session.fields.clone());
+    } else {
+        request.add_fields(config.fields);
+    }
 
-    Ok(())
+    if let Some(language) = &session.language {
+        request.set_language(language);
+    }
+
+    let response: place::Response = session.send(&request).await?;
+
+    output.print(&response, &response.candidates)
 }
 
 #[derive(Parser)]
@@ -65,13 +414,8 @@ enum NearbyAction {
 
 #[derive(Parser)]
 struct ProminenceArgs {
-    /// google api key
-    #[clap(long)]
-    token: String,
-
-    /// The point around which to retrieve place information. This must be specified as latitude,longitude.
-    #[clap(long)]
-    location: String,
+    #[clap(flatten)]
+    options: NearbyOptions,
 
     /// Defines the distance (in meters) within which to return place results.
     #[clap(long)]
@@ -79,73 +423,812 @@ struct ProminenceArgs {
 }
 
 #[derive(Parser)]
+#[clap(group(ArgGroup::new("filter").required(true).multiple(true).args(&["keyword", "request-type"])))]
 struct DistanceArgs {
-    /// google api key
+    #[clap(flatten)]
+    options: NearbyOptions,
+}
+
+// everything a nearby search takes besides its ranking, the language being the global --language.
+#[derive(Parser)]
+struct NearbyOptions {
+    /// The point around which to retrieve place information, as latitude,longitude, degrees, minutes and seconds,
+    /// a plus code, or an address that is looked up with Find Place.
     #[clap(long)]
-    token: String,
+    location: Location,
 
-    /// The point around which to retrieve place information. This must be specified as latitude,longitude.
+    /// A term to be matched against all content that Google has indexed for this place.
+    /// Ranking by distance needs it, --type, or both.
     #[clap(long)]
-    location: String,
+    keyword: Option<String>,
 
     /// Restricts the results to places matching the specified type.
     #[clap(long = "type")]
-    request_type: String,
+    request_type: Option<String>,
 
-    /// A term to be matched against all content that Google has indexed for this place.
+    /// Restricts results to places at this price level or above, from 0 (most affordable) to 4 (most expensive).
+    #[clap(long, parse(try_from_str = parse_price))]
+    minprice: Option<u8>,
+
+    /// Restricts results to places at this price level or below, from 0 (most affordable) to 4 (most expensive).
+    #[clap(long, parse(try_from_str = parse_price))]
+    maxprice: Option<u8>,
+
+    /// Returns only those places that are open for business at the time the query is sent.
     #[clap(long)]
-    keyword: String,
+    opennow: bool,
+
+    /// Returns the page of results a previous search gave this next_page_token for.
+    #[clap(long)]
+    pagetoken: Option<String>,
+
+    /// Follows next_page_token up to the last page, bypassing the cache, and prints every result at once.
+    #[clap(long)]
+    all_pages: bool,
 }
 
-async fn nearby(config: NearbyArgs) -> Result<(), Error> {
-    fn parse_location(location: &str) -> (f64, f64) {
-        let collect = location
-            .split(",")
-            .flat_map(|arg| arg.parse::<f64>())
-            .collect::<Vec<_>>();
+fn parse_price(value: &str) -> Result<u8, String> {
+    match value.parse() {
+        Ok(price @ 0..=4) => Ok(price),
+        _ => Err("a price level is a whole number from 0 to 4".to_owned()),
+    }
+}
+
+impl NearbyOptions {
+    async fn request(&self, session: &Session) -> Result<nearby::Request, Error> {
+        if let (Some(min), Some(max)) = (self.minprice, self.maxprice) {
+            if min > max {
+                return Err(format!("--minprice {} is above --maxprice {}", min, max).into());
+            }
+        }
+
+        let location = session.locate(&self.location).await?;
 
-        (collect[0], collect[1])
+        let mut request = session.client.nearby(location.lat, location.lng);
+        request.language = session.language.clone();
+        request.keyword = self.keyword.clone();
+        request.request_type = self.request_type.clone();
+        request.minprice = self.minprice.map(|price| price.to_string());
+        request.maxprice = self.maxprice.map(|price| price.to_string());
+        request.opennow = Some(self.opennow);
+        request.pagetoken = self.pagetoken.clone();
+
+        Ok(request)
     }
+}
 
-    let output: nearby::Response = match config.command {
+async fn nearby(session: &Session, config: NearbyArgs, output: &OutputArgs) -> Result<(), Error> {
+    let response: nearby::Response = match config.command {
         NearbyAction::Prominence(config) => {
-            let (lat, lng) = parse_location(&config.location);
-
-            Client::new(config.token)
-                .nearby(lat, lng)
-                .prominence(config.radius)
-                .send()
+            let request = config
+                .options
+                .request(session)
                 .await?
+                .prominence(config.radius);
+
+            if config.options.all_pages {
+                request
+                    .send_all_pages_with(|url, params| session.send_uncached(url, params))
+                    .await?
+            } else {
+                session.send(&request).await?
+            }
         }
 
         NearbyAction::Distance(config) => {
-            let (lat, lng) = parse_location(&config.location);
-
-            Client::new(config.token)
-                .nearby(lat, lng)
-                .distance()
-                .set_type(config.request_type)
-                .set_keyword(config.keyword)
-                .send()
-                .await?
+            let request = config.options.request(session).await?.distance();
+
+            // the argument group makes sure there is a type or a keyword.
+            let request = match (&config.options.request_type, &config.options.keyword) {
+                (Some(request_type), _) => request.set_type(request_type),
+                (None, Some(keyword)) => request.set_keyword(keyword),
+                (None, None) => return Err("ranking by distance needs --keyword or --type".into()),
+            };
+
+            if config.options.all_pages {
+                request
+                    .send_all_pages_with(|url, params| session.send_uncached(url, params))
+                    .await?
+            } else {
+                session.send(&request).await?
+            }
         }
     };
 
-    println!("{:#?}", output);
+    output.print(&response, &response.results)
+}
+
+#[derive(Parser)]
+struct DetailsArgs {
+    /// the place_id of the place, as returned by find or nearby.
+    #[clap(long)]
+    place_id: String,
+
+    /// specify a list of place data types to return, the profile's `fields` by default, every field otherwise.
+    #[clap(long, arg_enum, multiple_values = true)]
+    fields: Vec<details::Field>,
+}
+
+async fn details(session: &Session, config: DetailsArgs, output: &OutputArgs) -> Result<(), Error> {
+    let response = session
+        .details(&config.place_id, config.fields, None)
+        .await?;
+
+    output.print(&response, response.places())
+}
+
+#[derive(Parser)]
+#[clap(group(ArgGroup::new("photo").required(true).args(&["reference", "place-id"])))]
+struct PhotoArgs {
+    /// the photo_reference of a photo, from the `photos` of a place.
+    #[clap(long)]
+    reference: Option<String>,
+
+    /// download every photo of this place rather than a single one.
+    #[clap(long)]
+    place_id: Option<String>,
+
+    /// scale the photo down to this width in pixels, 1600 when no max height is given either.
+    #[clap(long, parse(try_from_str = parse_photo_size))]
+    max_width: Option<u32>,
+
+    /// scale the photo down to this height in pixels.
+    #[clap(long, parse(try_from_str = parse_photo_size))]
+    max_height: Option<u32>,
+
+    /// the file to write the photo to, or the directory to write the photos of --place-id to.
+    #[clap(long)]
+    out: PathBuf,
+}
+
+fn parse_photo_size(value: &str) -> Result<u32, String> {
+    match value.parse() {
+        Ok(size @ 1..=photo::MAX_SIZE) => Ok(size),
+        _ => Err(format!(
+            "a photo size is a number of pixels from 1 to {}",
+            photo::MAX_SIZE
+        )),
+    }
+}
+
+/// Writes the files and prints their paths, one per line.
+async fn photo(session: &Session, config: PhotoArgs) -> Result<(), Error> {
+    let request = |reference: &str| {
+        let mut request = session
+            .client
+            .photo(reference, config.max_width.unwrap_or(photo::MAX_SIZE));
+
+        if let Some(max_height) = config.max_height {
+            request.set_max_height(max_height);
+
+            // a max height alone bounds the photo, rather than the default width.
+            request.max_width = config.max_width;
+        }
+
+        request
+    };
+
+    if let Some(reference) = &config.reference {
+        let photo = session.download(&request(reference)).await?;
+        fs::write(&config.out, &photo.bytes)?;
+
+        println!("{}", config.out.display());
+
+        return Ok(());
+    }
+
+    let place_id = config.place_id.as_deref().unwrap_or_default();
+    let response = session
+        .details(place_id, vec![details::Field::Photo], None)
+        .await?;
+
+    let photos = match (response.status, response.result) {
+        (SearchStatus::Ok, Some(place)) => place.photos.unwrap_or_default(),
+        (status, _) => {
+            return Err(format!(
+                "cannot get the photos of {}: {:?} {}",
+                place_id,
+                status,
+                response.error_message.unwrap_or_default()
+            )
+            .into())
+        }
+    };
+
+    if photos.is_empty() {
+        return Err(format!("{} has no photos", place_id).into());
+    }
+
+    fs::create_dir_all(&config.out)?;
+
+    for (index, place_photo) in photos.iter().enumerate() {
+        let photo = session
+            .download(&request(&place_photo.photo_reference))
+            .await?;
+
+        let path = config
+            .out
+            .join(format!("{}.{}", index + 1, photo.extension()));
+        fs::write(&path, &photo.bytes)?;
+
+        println!("{}", path.display());
+    }
 
     Ok(())
 }
 
-#[tokio::main]
-async fn main() {
-    let result = match Cli::parse().command {
-        Commands::Find(config) => find(config).await,
-        Commands::Nearby(config) => nearby(config).await,
+/// Resolves a spreadsheet of places, such as business names and addresses, to place_ids.
+#[derive(Parser)]
+struct BatchArgs {
+    /// a CSV file with a header row, or an NDJSON file with an object per line, told apart by their extension.
+    #[clap(long)]
+    input: PathBuf,
+
+    /// where to write each row followed by its match, in the format of the input.
+    #[clap(long)]
+    out: PathBuf,
+
+    /// the Find Place input of each row, with the columns to fill in named in braces.
+    #[clap(long, default_value = "{name} {address}")]
+    template: String,
+
+    /// the type of input.
+    #[clap(long, arg_enum, default_value = "text-query")]
+    input_type: place::InputType,
+
+    /// the column holding the name the candidates are matched against, the Find Place input when a row has none.
+    #[clap(long, default_value = "name")]
+    name_column: String,
+
+    /// the column holding the address the candidates are matched against, if any.
+    #[clap(long, default_value = "address")]
+    address_column: String,
+
+    /// the score from 0 to 1 a candidate needs to match, when no other candidate scores close to it.
+    #[clap(long, default_value = "0.6", parse(try_from_str = parse_threshold))]
+    threshold: f64,
+
+    /// specify a list of place data types to return, the profile's `fields` by default.
+    #[clap(long, arg_enum, multiple_values = true)]
+    fields: Vec<place::Field>,
+
+    /// how many requests may run at the same time.
+    #[clap(long, default_value = "4")]
+    concurrency: usize,
+
+    /// requests sent per second at most, instead of the profile's `rate_limit`.
+    #[clap(long, parse(try_from_str = parse_qps))]
+    qps: Option<f64>,
+
+    /// continue an interrupted run, skipping the rows already written to --out
+    /// except those whose search failed or hit OVER_QUERY_LIMIT, which are searched again.
+    #[clap(long)]
+    resume: bool,
+}
+
+fn parse_threshold(value: &str) -> Result<f64, String> {
+    match value.parse() {
+        Ok(threshold) if (0.0..=1.0).contains(&threshold) => Ok(threshold),
+        _ => Err("a match threshold is a number from 0 to 1".to_owned()),
+    }
+}
+
+fn parse_qps(value: &str) -> Result<f64, String> {
+    match value.parse() {
+        Ok(qps) if qps > 0.0 => Ok(qps),
+        _ => Err("requests per second must be a number above 0".to_owned()),
+    }
+}
+
+/// The place columns a batch adds to each row when --columns is not given.
+const BATCH_COLUMNS: [Column; 5] = [
+    Column::PlaceId,
+    Column::Name,
+    Column::Address,
+    Column::Lat,
+    Column::Lng,
+];
+
+/// The fields a batch asks for when neither --fields nor the profile gives any.
+const BATCH_FIELDS: [place::Field; 4] = [
+    place::Field::PlaceId,
+    place::Field::Name,
+    place::Field::FormattedAddress,
+    place::Field::Geometry,
+];
+
+/// What a row is matched on: its name and address columns, the Find Place `input` standing in for a missing name.
+fn match_query(row: &rows::Row, input: &str, config: &BatchArgs) -> place::MatchQuery {
+    let column =
+        |name: &str| Some(rows::cell(row.get(name))).filter(|text| !text.trim().is_empty());
+
+    place::MatchQuery {
+        name: column(&config.name_column).or_else(|| Some(input.to_owned())),
+        address: column(&config.address_column),
+        ..Default::default()
+    }
+}
+
+/// Statuses of searches that would get the same answer again, unlike a failed request or OVER_QUERY_LIMIT.
+const FINAL_STATUSES: [&str; 3] = ["OK", "ZERO_RESULTS", "EMPTY_INPUT"];
+
+/// Whether a row written by an earlier run can be kept as it is when resuming.
+fn is_final(row: &rows::Row) -> bool {
+    row.get("match_status")
+        .and_then(Value::as_str)
+        .is_some_and(|status| FINAL_STATUSES.contains(&status))
+}
+
+/// Adds to `row` the status of its search, why it failed, how many candidates it found,
+/// whether one of them matches `query` and that one. Returns the outcome the summary counts the row under.
+fn add_match(
+    row: &mut rows::Row,
+    result: Result<Option<place::Response>, Error>,
+    query: &place::MatchQuery,
+    threshold: f64,
+    columns: &[Column],
+) -> String {
+    let (status, error, candidates) = match result {
+        Ok(Some(response)) => {
+            let status = serde_json::to_value(response.status)
+                .ok()
+                .and_then(|status| status.as_str().map(str::to_owned))
+                .unwrap_or_default();
+
+            (status, response.error_message, Some(response.candidates))
+        }
+        Ok(None) => ("EMPTY_INPUT".to_owned(), None, None),
+        Err(error) => ("ERROR".to_owned(), Some(error.to_string()), None),
+    };
+
+    let matched = match (status.as_str(), &candidates) {
+        ("OK" | "ZERO_RESULTS", Some(candidates)) => {
+            Some(place::Matcher::new().best_match(query, candidates, threshold))
+        }
+        _ => None,
+    };
+
+    let (outcome, found) = match matched {
+        Some(place::BestMatch::Unique(found)) => (Some("MATCHED"), Some(found)),
+        Some(place::BestMatch::Ambiguous(_)) => (Some("AMBIGUOUS"), None),
+        Some(place::BestMatch::NotFound) => (Some("NOT_FOUND"), None),
+        None => (None, None),
+    };
+
+    row.insert("match_status".to_owned(), Value::from(status.clone()));
+    row.insert(
+        "match_error".to_owned(),
+        error.map_or(Value::Null, Value::from),
+    );
+    row.insert(
+        "match_candidates".to_owned(),
+        Value::from(candidates.as_ref().map_or(0, Vec::len)),
+    );
+    row.insert(
+        "match_result".to_owned(),
+        outcome.map_or(Value::Null, Value::from),
+    );
+    row.insert(
+        "match_score".to_owned(),
+        found.map_or(Value::Null, |found| {
+            Value::from((found.score.total * 100.0).round() / 100.0)
+        }),
+    );
+
+    for column in columns {
+        row.insert(
+            format!("match_{}", column),
+            Value::from(
+                found
+                    .map(|found| column.value(found.place))
+                    .unwrap_or_default(),
+            ),
+        );
+    }
+
+    outcome.map_or(status, str::to_owned)
+}
+
+async fn batch(session: &mut Session, config: BatchArgs, output: &OutputArgs) -> Result<(), Error> {
+    let input = Rows::read(&config.input)?;
+    let template = Template::parse(&config.template)?;
+
+    if input.format == Format::Csv {
+        if let Some(column) = template
+            .columns()
+            .find(|column| !input.header.contains(&column.to_string()))
+        {
+            return Err(format!(
+                "the template names a column {:?}, the input has: {}",
+                column,
+                input.header.join(", ")
+            )
+            .into());
+        }
+    }
+
+    if let Some(qps) = config.qps {
+        session.limiter = Some(RateLimiter::new(qps));
+    }
+
+    let columns = if output.columns.is_empty() {
+        &BATCH_COLUMNS[..]
+    } else {
+        &output.columns[..]
+    };
+
+    let mut fields = match (config.fields.is_empty(), session.fields.is_empty()) {
+        (false, _) => config.fields.clone(),
+        (true, false) => session.fields.clone(),
+        (true, true) => BATCH_FIELDS.to_vec(),
+    };
+
+    // candidates are matched on their name and address.
+    if !fields
+        .iter()
+        .any(|field| matches!(field, place::Field::Name))
+    {
+        fields.push(place::Field::Name);
+    }
+
+    if !fields
+        .iter()
+        .any(|field| matches!(field, place::Field::FormattedAddress))
+    {
+        fields.push(place::Field::FormattedAddress);
+    }
+
+    let mut header = input.header.clone();
+    header.extend(
+        [
+            "match_status",
+            "match_error",
+            "match_candidates",
+            "match_result",
+            "match_score",
+        ]
+        .map(str::to_owned),
+    );
+    header.extend(columns.iter().map(|column| format!("match_{}", column)));
+
+    let (mut out, written) = Output::open(&config.out, input.format, header, config.resume)?;
+
+    // rows written with a final status are kept, the others are searched again.
+    let kept = |index: usize| written.get(index).filter(|row| is_final(row));
+    let done = (0..input.rows.len())
+        .take_while(|&index| kept(index).is_some())
+        .count();
+
+    if done < written.len() {
+        out.rewrite(&written[..done])?;
+    }
+
+    let pending = (done..input.rows.len())
+        .filter(|&index| kept(index).is_none())
+        .collect::<Vec<_>>();
+
+    let session = &*session;
+    let requests = pending.iter().map(|&index| {
+        let row = &input.rows[index];
+        let mut request = session
+            .client
+            .find(template.render(row), config.input_type.to_string());
+        request.add_fields(fields.clone());
+
+        if let Some(language) = &session.language {
+            request.set_language(language);
+        }
+
+        request
+    });
+
+    let mut results = session
+        .client
+        .batch(requests)
+        .set_concurrency(config.concurrency)
+        .run(|request| async move {
+            if request.input.is_empty() {
+                return Ok(None);
+            }
+
+            session.send(&request).await.map(Some)
+        });
+
+    let mut statuses = BTreeMap::<String, usize>::new();
+
+    let mut next = done;
+
+    while let Some(item) = results.next().await {
+        let index = pending[item.index];
+
+        // the rows kept from the earlier run go back in their place.
+        for row in (next..index).filter_map(kept) {
+            out.write(row)?;
+        }
+
+        next = index + 1;
+
+        let mut row = input.rows[index].clone();
+        let query = match_query(&row, &template.render(&row), &config);
+        let status = add_match(&mut row, item.result, &query, config.threshold, columns);
+
+        out.write(&row)?;
+        *statuses.entry(status).or_default() += 1;
+    }
+
+    for row in (next..input.rows.len()).filter_map(kept) {
+        out.write(row)?;
+    }
+
+    let mut summary = format!(
+        "{} rows resolved, {} already done",
+        pending.len(),
+        input.rows.len() - pending.len()
+    );
+
+    for (status, count) in &statuses {
+        summary.push_str(&format!(", {} {}", count, status));
+    }
+
+    eprintln!("{}", summary);
+
+    Ok(())
+}
+
+/// Type a place, pick one of the predictions with the arrow keys and Enter to print its details,
+/// Esc to quit.
+#[derive(Parser)]
+struct ReplArgs {
+    /// bias the predictions towards this point, as latitude,longitude, a plus code or an address.
+    #[clap(long)]
+    location: Option<Location>,
+
+    /// the distance in meters around --location to bias the predictions towards, 50000 by default.
+    #[clap(long, requires = "location")]
+    radius: Option<u32>,
+
+    /// restrict the predictions to these countries, as two-letter codes such as "au".
+    #[clap(long, use_delimiter = true)]
+    country: Vec<String>,
+
+    /// specify a list of place data types to print, the profile's `fields` by default, every field otherwise.
+    #[clap(long, arg_enum, multiple_values = true)]
+    fields: Vec<details::Field>,
+}
+
+/// How long typing must pause before predictions are requested.
+const REPL_DEBOUNCE: Duration = Duration::from_millis(250);
+
+/// Leaves raw mode however the REPL ends.
+struct RawMode;
+
+impl RawMode {
+    fn enable() -> Result<RawMode, Error> {
+        terminal::enable_raw_mode()?;
+
+        Ok(RawMode)
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+async fn repl(session: &Session, config: ReplArgs, output: &OutputArgs) -> Result<(), Error> {
+    if !io::stdin().is_tty() {
+        return Err("repl reads keys from a terminal".into());
+    }
+
+    let location = match &config.location {
+        Some(location) => Some(session.locate(location).await?),
+        None => None,
     };
 
-    if let Err(err) = result {
+    // one token from the first keystroke up to the details of the pick, then a new one.
+    let mut session_token = autocomplete::new_session_token();
+    let mut prompt = repl::Prompt::default();
+    let mut screen = io::stderr();
+    let mut typed_at: Option<Instant> = None;
+
+    let raw_mode = RawMode::enable()?;
+    prompt.draw(&mut screen)?;
+
+    loop {
+        let timeout = typed_at.map_or(REPL_DEBOUNCE, |at| {
+            REPL_DEBOUNCE.saturating_sub(at.elapsed())
+        });
+
+        if !event::poll(timeout)? {
+            if typed_at.take().is_none() {
+                continue;
+            }
+
+            if prompt.input.trim().is_empty() {
+                prompt.set_predictions(vec![]);
+            } else {
+                let mut request = session.client.autocomplete(prompt.input.trim());
+                request.set_session_token(&session_token);
+
+                if let Some(language) = &session.language {
+                    request.set_language(language);
+                }
+
+                if let Some(location) = location {
+                    request.set_location(location, config.radius.unwrap_or(50000));
+                }
+
+                for country in &config.country {
+                    request.add_country(country);
+                }
+
+                match session.send::<_, autocomplete::Response>(&request).await {
+                    Ok(response) => {
+                        let message = match response.status {
+                            SearchStatus::Ok | SearchStatus::ZeroResults => None,
+                            status => Some(format!(
+                                "{:?} {}",
+                                status,
+                                response.error_message.unwrap_or_default()
+                            )),
+                        };
+
+                        prompt.set_predictions(response.predictions);
+                        prompt.message = message;
+                    }
+                    Err(error) => prompt.message = Some(error.to_string()),
+                }
+            }
+
+            prompt.draw(&mut screen)?;
+            continue;
+        }
+
+        let key = match event::read()? {
+            Event::Key(key) => key,
+            Event::Resize(..) => {
+                prompt.draw(&mut screen)?;
+                continue;
+            }
+            _ => continue,
+        };
+
+        match prompt.handle(key) {
+            repl::Action::Edit => {
+                typed_at = Some(Instant::now());
+                prompt.draw(&mut screen)?;
+            }
+            repl::Action::Redraw => prompt.draw(&mut screen)?,
+            repl::Action::Nothing => {}
+            repl::Action::Quit => break,
+            repl::Action::Pick(index) => {
+                let place_id = match &prompt.predictions[index].place_id {
+                    Some(place_id) => place_id.clone(),
+                    None => {
+                        prompt.message = Some("this prediction has no place_id".to_owned());
+                        prompt.draw(&mut screen)?;
+                        continue;
+                    }
+                };
+
+                prompt.erase(&mut screen)?;
+                terminal::disable_raw_mode()?;
+
+                eprintln!("> {}", prompt.predictions[index].description);
+
+                match session
+                    .details(&place_id, config.fields.clone(), Some(&session_token))
+                    .await
+                {
+                    Ok(response) => {
+                        output.print(&response, response.places())?;
+
+                        session_token = autocomplete::new_session_token();
+                        prompt = repl::Prompt::default();
+                        typed_at = None;
+                    }
+                    // the predictions stay, to pick again.
+                    Err(error) => prompt.message = Some(error.to_string()),
+                }
+
+                terminal::enable_raw_mode()?;
+                prompt.draw(&mut screen)?;
+            }
+        }
+    }
+
+    prompt.erase(&mut screen)?;
+    drop(raw_mode);
+
+    Ok(())
+}
+
+async fn run(mut cli: Cli) -> Result<(), Error> {
+    let mut session = Session::open(&mut cli)?;
+
+    match cli.command {
+        Commands::Find(config) => find(&session, config, &cli.output).await,
+        Commands::Nearby(config) => nearby(&session, config, &cli.output).await,
+        Commands::Details(config) => details(&session, config, &cli.output).await,
+        Commands::Photo(config) => photo(&session, config).await,
+        Commands::Batch(config) => batch(&mut session, config, &cli.output).await,
+        Commands::Repl(config) => repl(&session, config, &cli.output).await,
+    }
+}
+
+#[tokio::main]
+async fn main() {
+    if let Err(err) = run(Cli::parse()).await {
         eprintln!("Error: {}", err);
 
         process::exit(1);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{add_match, is_final, BATCH_COLUMNS};
+    use google_place_api::place;
+    use serde_json::{json, Value};
+
+    fn response(status: &str, addresses: &[&str]) -> place::Response {
+        let candidates = addresses
+            .iter()
+            .enumerate()
+            .map(|(index, address)| {
+                json!({
+                    "place_id": format!("place-{}", index),
+                    "name": "Gelato Messina",
+                    "formatted_address": address
+                })
+            })
+            .collect::<Vec<_>>();
+
+        serde_json::from_value(json!({ "candidates": candidates, "status": status })).unwrap()
+    }
+
+    #[test]
+    fn test_add_match() {
+        let branches = [
+            "241 Victoria St, Darlinghurst NSW 2010, Australia",
+            "Shop 7, The Galeries, 500 George Street, Sydney",
+        ];
+        let matched = |query: &place::MatchQuery, result| {
+            let mut row = serde_json::Map::new();
+            let outcome = add_match(&mut row, result, query, 0.6, &BATCH_COLUMNS);
+
+            (outcome, Value::Object(row))
+        };
+
+        let name = place::MatchQuery::new("Gelato Messina");
+        let (outcome, row) = matched(&name, Ok(Some(response("OK", &branches))));
+        assert_eq!(outcome, "AMBIGUOUS");
+        assert_eq!(row["match_status"], "OK");
+        assert_eq!(row["match_candidates"], 2);
+        assert_eq!(row["match_place_id"], "");
+
+        let address = name.clone().set_address(branches[1]);
+        let (outcome, row) = matched(&address, Ok(Some(response("OK", &branches))));
+        assert_eq!(outcome, "MATCHED");
+        assert_eq!(row["match_result"], "MATCHED");
+        assert_eq!(row["match_place_id"], "place-1");
+        assert!(row["match_score"].as_f64().unwrap() >= 0.6);
+
+        let (outcome, row) = matched(&name, Ok(Some(response("ZERO_RESULTS", &[]))));
+        assert_eq!(outcome, "NOT_FOUND");
+        assert_eq!(row["match_status"], "ZERO_RESULTS");
+        assert!(is_final(row.as_object().unwrap()));
+
+        let (outcome, row) = matched(&name, Err("connection reset".into()));
+        assert_eq!(outcome, "ERROR");
+        assert_eq!(row["match_error"], "connection reset");
+        assert!(row["match_result"].is_null());
+        assert!(!is_final(row.as_object().unwrap()));
+
+        // searched again on --resume.
+        let (outcome, row) = matched(&name, Ok(Some(response("OVER_QUERY_LIMIT", &[]))));
+        assert_eq!(outcome, "OVER_QUERY_LIMIT");
+        assert!(!is_final(row.as_object().unwrap()));
+    }
+}
@@ -0,0 +1,18 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum BusinessStatus {
+    /// indicating the place is open for business.
+    Operational,
+
+    /// indicating the place is temporarily closed, for example for renovation.
+    ClosedTemporarily,
+
+    /// indicating the place has closed for good.
+    ClosedPermanently,
+
+    /// indicating a status this crate does not know about yet.
+    #[serde(other)]
+    Unknown,
+}
@@ -0,0 +1,297 @@
+use crate::models::LatLng;
+use std::fmt;
+use std::str::FromStr;
+
+/// Why a text could not be read as a location.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseLatLngError {
+    pub input: String,
+    pub reason: String,
+}
+
+impl fmt::Display for ParseLatLngError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid location {:?}: {}", self.input, self.reason)
+    }
+}
+
+impl std::error::Error for ParseLatLngError {}
+
+const PLUS_CODE_ALPHABET: &str = "23456789CFGHJMPQRVWX";
+
+/// The center of the area a full plus code, such as "8FVC9G8F+6X", stands for.
+fn decode_plus_code(code: &str) -> Result<LatLng, String> {
+    let code = code.to_ascii_uppercase();
+
+    match code.find('+') {
+        Some(8) => {}
+        Some(position) if position < 8 => {
+            return Err("short plus codes need a locality, such as \"9G8F+6X Zurich\"".to_owned())
+        }
+        _ => return Err("a plus code has its '+' after the eighth character".to_owned()),
+    }
+
+    let digits = code
+        .chars()
+        .filter(|&c| c != '+')
+        .collect::<String>()
+        .trim_end_matches('0')
+        .chars()
+        .map(|c| {
+            PLUS_CODE_ALPHABET
+                .find(c)
+                .map(|value| value as f64)
+                .ok_or_else(|| format!("{:?} is not a plus code character", c))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if digits.len() < 2 || digits.len() % 2 == 1 && digits.len() < 10 {
+        return Err(
+            "a plus code has an even number of digits before its grid refinement".to_owned(),
+        );
+    }
+
+    let (mut lat, mut lng) = (-90.0, -180.0);
+    let (mut lat_size, mut lng_size) = (400.0, 400.0);
+
+    for (i, digit) in digits.iter().enumerate() {
+        if i < 10 {
+            if i % 2 == 0 {
+                lat_size /= 20.0;
+                lat += digit * lat_size;
+            } else {
+                lng_size /= 20.0;
+                lng += digit * lng_size;
+            }
+        } else {
+            lat_size /= 5.0;
+            lng_size /= 4.0;
+            lat += (digit / 4.0).floor() * lat_size;
+            lng += (digit % 4.0) * lng_size;
+        }
+    }
+
+    Ok(LatLng::new(lat + lat_size / 2.0, lng + lng_size / 2.0))
+}
+
+fn is_hemisphere(c: char) -> bool {
+    matches!(c.to_ascii_uppercase(), 'N' | 'S' | 'E' | 'W')
+}
+
+/// One coordinate, in decimal degrees or degrees, minutes and seconds, with an optional hemisphere letter
+/// before or after it. Returns the value and the hemisphere letter, if any.
+fn parse_coordinate(text: &str) -> Result<(f64, Option<char>), String> {
+    let text = text.trim();
+
+    let (hemisphere, rest) = match (text.chars().next(), text.chars().last()) {
+        (Some(first), _) if is_hemisphere(first) => (Some(first), &text[1..]),
+        (_, Some(last)) if is_hemisphere(last) => (Some(last), &text[..text.len() - 1]),
+        _ => (None, text),
+    };
+
+    let parts = rest
+        .split(|c: char| !(c.is_ascii_digit() || c == '.' || c == '-' || c == '+'))
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            part.parse::<f64>()
+                .map_err(|_| format!("{:?} is not a number", part))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let (degrees, minutes, seconds) = match parts[..] {
+        [degrees] => (degrees, 0.0, 0.0),
+        [degrees, minutes] => (degrees, minutes, 0.0),
+        [degrees, minutes, seconds] => (degrees, minutes, seconds),
+        _ => return Err(format!("{:?} is not a coordinate", text)),
+    };
+
+    if !(0.0..60.0).contains(&minutes) || !(0.0..60.0).contains(&seconds) {
+        return Err(format!(
+            "minutes and seconds of {:?} must be below 60",
+            text
+        ));
+    }
+
+    let magnitude = degrees.abs() + minutes / 60.0 + seconds / 3600.0;
+    let negative = degrees.is_sign_negative()
+        || hemisphere.is_some_and(|h| matches!(h.to_ascii_uppercase(), 'S' | 'W'));
+
+    if degrees.is_sign_negative() && hemisphere.is_some() {
+        return Err(format!("{:?} has both a sign and a hemisphere", text));
+    }
+
+    Ok((if negative { -magnitude } else { magnitude }, hemisphere))
+}
+
+/// Splits a pair of coordinates written without a comma, on its hemisphere letters or its single space.
+fn split_pair(text: &str) -> Option<(&str, &str)> {
+    let letters = text
+        .char_indices()
+        .filter(|(_, c)| is_hemisphere(*c))
+        .map(|(i, _)| i)
+        .collect::<Vec<_>>();
+
+    match letters[..] {
+        // "S 33 51 54 E 151 12 36"
+        [0, second] => Some((&text[..second], &text[second..])),
+        // "33 51 54 S 151 12 36 E"
+        [first, _] => Some((&text[..=first], &text[first + 1..])),
+        [] => match text.split_whitespace().collect::<Vec<_>>()[..] {
+            [lat, lng] => Some((lat, lng)),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// A latitude and a longitude, latitude first unless hemisphere letters say otherwise.
+fn parse_pair(text: &str) -> Result<(f64, f64), String> {
+    let (first, second) = match text.split(',').collect::<Vec<_>>()[..] {
+        [first, second] => (first, second),
+        [_] => split_pair(text).ok_or("expected a latitude and a longitude")?,
+        _ => return Err("expected a single comma between latitude and longitude".to_owned()),
+    };
+
+    let (a, a_hemisphere) = parse_coordinate(first)?;
+    let (b, b_hemisphere) = parse_coordinate(second)?;
+
+    let is_longitude =
+        |h: Option<char>| h.is_some_and(|h| matches!(h.to_ascii_uppercase(), 'E' | 'W'));
+    let is_latitude =
+        |h: Option<char>| h.is_some_and(|h| matches!(h.to_ascii_uppercase(), 'N' | 'S'));
+
+    if is_longitude(a_hemisphere) || is_latitude(b_hemisphere) {
+        if is_longitude(b_hemisphere) || is_latitude(a_hemisphere) {
+            return Err("both coordinates are on the same axis".to_owned());
+        }
+
+        Ok((b, a))
+    } else {
+        Ok((a, b))
+    }
+}
+
+/// Reads "lat,lng" in decimal degrees, with any whitespace, degrees, minutes and seconds such as
+/// `33°51'54"S 151°12'36"E`, or a full plus code such as "4RRH46J5+FP". Latitude comes first
+/// unless hemisphere letters say otherwise.
+impl FromStr for LatLng {
+    type Err = ParseLatLngError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let error = |reason: String| ParseLatLngError {
+            input: input.to_owned(),
+            reason,
+        };
+
+        let text = input.trim();
+
+        let (lat, lng) = if text.contains('+') && !text.contains([',', ' ']) && text.len() > 8 {
+            decode_plus_code(text).map(|location| (location.lat, location.lng))
+        } else {
+            parse_pair(text)
+        }
+        .map_err(error)?;
+
+        if !(-90.0..=90.0).contains(&lat) {
+            return Err(error(format!("latitude {} is not between -90 and 90", lat)));
+        }
+
+        if !(-180.0..=180.0).contains(&lng) {
+            return Err(error(format!(
+                "longitude {} is not between -180 and 180",
+                lng
+            )));
+        }
+
+        Ok(LatLng::new(lat, lng))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::LatLng;
+
+    fn parse(text: &str) -> LatLng {
+        text.parse().unwrap()
+    }
+
+    fn assert_near(text: &str, lat: f64, lng: f64) {
+        let location = parse(text);
+
+        assert!(
+            (location.lat - lat).abs() < 1e-6 && (location.lng - lng).abs() < 1e-6,
+            "{} parsed as {:?}",
+            text,
+            location
+        );
+    }
+
+    #[test]
+    fn test_parse_decimal() {
+        assert_eq!(parse("-33.8587,151.2140"), LatLng::new(-33.8587, 151.2140));
+        assert_eq!(
+            parse("  -33.8587 ,  151.2140 "),
+            LatLng::new(-33.8587, 151.2140)
+        );
+        assert_eq!(parse("-33.8587 151.2140"), LatLng::new(-33.8587, 151.2140));
+        assert_eq!(parse("90,-180"), LatLng::new(90.0, -180.0));
+    }
+
+    #[test]
+    fn test_parse_dms() {
+        let (lat, lng) = (
+            -(33.0 + 51.0 / 60.0 + 54.0 / 3600.0),
+            151.0 + 12.0 / 60.0 + 36.0 / 3600.0,
+        );
+
+        assert_near("33°51'54\"S 151°12'36\"E", lat, lng);
+        assert_near("33°51′54″S, 151°12′36″E", lat, lng);
+        assert_near("S 33 51 54 E 151 12 36", lat, lng);
+        assert_near("151°12'36\"E 33°51'54\"S", lat, lng);
+        assert_near("33 51.9 S, 151 12.6 E", lat, lng);
+        assert_near("-33°51'54\", 151°12'36\"", lat, lng);
+    }
+
+    #[test]
+    fn test_parse_plus_code() {
+        assert_near("8FVC9G8F+6X", 47.3655625, 8.5249375);
+        assert_near("8fvc9g8f+6xq", 47.3655875, 8.524984375);
+        assert_near("8FVC0000+", 47.5, 8.5);
+    }
+
+    #[test]
+    fn test_reject_invalid_locations() {
+        for text in [
+            "",
+            "-33.8587",
+            "-33.8587,",
+            "1,2,3",
+            "abc,151.2",
+            "91,0",
+            "0,180.5",
+            "33°61'S 151°E",
+            "33°S 151°N",
+            "-33°S 151°E",
+            "9G8F+6X",
+            "8FVC9G8F+6I",
+            "XX222222+22",
+        ] {
+            assert!(
+                text.parse::<LatLng>().is_err(),
+                "{:?} should not parse",
+                text
+            );
+        }
+
+        assert_eq!(
+            "91,0".parse::<LatLng>().unwrap_err().to_string(),
+            "invalid location \"91,0\": latitude 91 is not between -90 and 90"
+        );
+
+        // the first pair of a plus code goes up to 9 and R, the highest digits would be outside the globe.
+        assert_eq!(
+            "XX222222+22".parse::<LatLng>().unwrap_err().reason,
+            "latitude 290.0000625 is not between -90 and 90"
+        );
+    }
+}
@@ -0,0 +1,69 @@
+use crate::models::{LatLng, Place};
+use std::cmp::Ordering;
+
+/// A place together with where it lies relative to a search origin.
+#[derive(Debug, Clone, Copy)]
+pub struct AnnotatedPlace<'a> {
+    pub place: &'a Place,
+
+    /// Great-circle distance in meters from the origin.
+    pub distance: f64,
+
+    /// Initial bearing in degrees clockwise from north, from the origin to the place.
+    pub bearing: f64,
+}
+
+impl Place {
+    /// The place's `geometry.location`.
+    pub fn location(&self) -> Option<&LatLng> {
+        self.geometry.as_ref().map(|geometry| &geometry.location)
+    }
+
+    /// Great-circle distance in meters from `origin`, if the place has a location.
+    pub fn distance_from(&self, origin: &LatLng) -> Option<f64> {
+        self.location()
+            .map(|location| origin.haversine_distance(location))
+    }
+
+    pub fn annotate(&self, origin: &LatLng) -> Option<AnnotatedPlace<'_>> {
+        let location = self.location()?;
+
+        Some(AnnotatedPlace {
+            place: self,
+            distance: origin.haversine_distance(location),
+            bearing: origin.initial_bearing(location),
+        })
+    }
+}
+
+/// Annotates the places that have a location, keeping their order.
+pub fn annotate_places<'a>(
+    places: impl IntoIterator<Item = &'a Place>,
+    origin: &LatLng,
+) -> Vec<AnnotatedPlace<'a>> {
+    places
+        .into_iter()
+        .filter_map(|place| place.annotate(origin))
+        .collect()
+}
+
+/// Sorts places from the nearest to the farthest from `origin`, places without a location last.
+pub fn sort_places_by_distance(places: &mut [Place], origin: &LatLng) {
+    places.sort_by(
+        |a, b| match (a.distance_from(origin), b.distance_from(origin)) {
+            (Some(a), Some(b)) => a.partial_cmp(&b).unwrap_or(Ordering::Equal),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        },
+    );
+}
+
+/// Keeps only the places within `max_distance` meters of `origin`.
+pub fn retain_places_within(places: &mut Vec<Place>, origin: &LatLng, max_distance: f64) {
+    places.retain(|place| {
+        place
+            .distance_from(origin)
+            .is_some_and(|distance| distance <= max_distance)
+    });
+}
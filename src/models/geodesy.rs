@@ -0,0 +1,494 @@
+use crate::models::{Bounds, LatLng};
+
+/// Mean radius of the earth in meters, as used by the haversine formula.
+pub const EARTH_RADIUS: f64 = 6_371_008.8;
+
+/// WGS-84 ellipsoid semi-major axis, flattening and semi-minor axis, as used by Vincenty's formula.
+const WGS84_A: f64 = 6_378_137.0;
+const WGS84_F: f64 = 1.0 / 298.257_223_563;
+const WGS84_B: f64 = WGS84_A * (1.0 - WGS84_F);
+
+/// Wraps a longitude into -180..180 degrees.
+fn normalize_lng(lng: f64) -> f64 {
+    (lng + 540.0).rem_euclid(360.0) - 180.0
+}
+
+impl LatLng {
+    pub fn new(lat: f64, lng: f64) -> LatLng {
+        LatLng { lat, lng }
+    }
+
+    /// Great-circle distance in meters on a spherical earth.
+    pub fn haversine_distance(&self, other: &LatLng) -> f64 {
+        let (lat1, lat2) = (self.lat.to_radians(), other.lat.to_radians());
+        let d_lat = lat2 - lat1;
+        let d_lng = (other.lng - self.lng).to_radians();
+
+        let a = (d_lat / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * (d_lng / 2.0).sin().powi(2);
+
+        2.0 * EARTH_RADIUS * a.sqrt().atan2((1.0 - a).sqrt())
+    }
+
+    /// Distance in meters on the WGS-84 ellipsoid, accurate to within a millimeter.
+    /// Returns `None` when the formula fails to converge, which happens for nearly antipodal points.
+    pub fn vincenty_distance(&self, other: &LatLng) -> Option<f64> {
+        let l = (other.lng - self.lng).to_radians();
+        let u1 = ((1.0 - WGS84_F) * self.lat.to_radians().tan()).atan();
+        let u2 = ((1.0 - WGS84_F) * other.lat.to_radians().tan()).atan();
+        let (sin_u1, cos_u1) = u1.sin_cos();
+        let (sin_u2, cos_u2) = u2.sin_cos();
+
+        let mut lambda = l;
+
+        for _ in 0..200 {
+            let (sin_lambda, cos_lambda) = lambda.sin_cos();
+
+            let sin_sigma = ((cos_u2 * sin_lambda).powi(2)
+                + (cos_u1 * sin_u2 - sin_u1 * cos_u2 * cos_lambda).powi(2))
+            .sqrt();
+
+            if sin_sigma == 0.0 {
+                return Some(0.0);
+            }
+
+            let cos_sigma = sin_u1 * sin_u2 + cos_u1 * cos_u2 * cos_lambda;
+            let sigma = sin_sigma.atan2(cos_sigma);
+            let sin_alpha = cos_u1 * cos_u2 * sin_lambda / sin_sigma;
+            let cos_sq_alpha = 1.0 - sin_alpha * sin_alpha;
+
+            // both points on the equator.
+            let cos_2_sigma_m = if cos_sq_alpha == 0.0 {
+                0.0
+            } else {
+                cos_sigma - 2.0 * sin_u1 * sin_u2 / cos_sq_alpha
+            };
+
+            let c = WGS84_F / 16.0 * cos_sq_alpha * (4.0 + WGS84_F * (4.0 - 3.0 * cos_sq_alpha));
+
+            let previous = lambda;
+
+            lambda = l
+                + (1.0 - c)
+                    * WGS84_F
+                    * sin_alpha
+                    * (sigma
+                        + c * sin_sigma
+                            * (cos_2_sigma_m
+                                + c * cos_sigma * (-1.0 + 2.0 * cos_2_sigma_m * cos_2_sigma_m)));
+
+            if (lambda - previous).abs() < 1e-12 {
+                let u_sq =
+                    cos_sq_alpha * (WGS84_A * WGS84_A - WGS84_B * WGS84_B) / (WGS84_B * WGS84_B);
+                let a = 1.0
+                    + u_sq / 16384.0 * (4096.0 + u_sq * (-768.0 + u_sq * (320.0 - 175.0 * u_sq)));
+                let b = u_sq / 1024.0 * (256.0 + u_sq * (-128.0 + u_sq * (74.0 - 47.0 * u_sq)));
+
+                let delta_sigma = b
+                    * sin_sigma
+                    * (cos_2_sigma_m
+                        + b / 4.0
+                            * (cos_sigma * (-1.0 + 2.0 * cos_2_sigma_m * cos_2_sigma_m)
+                                - b / 6.0
+                                    * cos_2_sigma_m
+                                    * (-3.0 + 4.0 * sin_sigma * sin_sigma)
+                                    * (-3.0 + 4.0 * cos_2_sigma_m * cos_2_sigma_m)));
+
+                return Some(WGS84_B * a * (sigma - delta_sigma));
+            }
+        }
+
+        None
+    }
+
+    /// Initial bearing in degrees clockwise from north, in 0..360, to follow the great circle to `other`.
+    pub fn initial_bearing(&self, other: &LatLng) -> f64 {
+        let (lat1, lat2) = (self.lat.to_radians(), other.lat.to_radians());
+        let d_lng = (other.lng - self.lng).to_radians();
+
+        let y = d_lng.sin() * lat2.cos();
+        let x = lat1.cos() * lat2.sin() - lat1.sin() * lat2.cos() * d_lng.cos();
+
+        y.atan2(x).to_degrees().rem_euclid(360.0)
+    }
+
+    /// The point reached after travelling `distance` meters along the great circle starting at `bearing` degrees.
+    pub fn destination(&self, bearing: f64, distance: f64) -> LatLng {
+        let lat1 = self.lat.to_radians();
+        let lng1 = self.lng.to_radians();
+        let bearing = bearing.to_radians();
+        let delta = distance / EARTH_RADIUS;
+
+        let lat2 = (lat1.sin() * delta.cos() + lat1.cos() * delta.sin() * bearing.cos()).asin();
+        let lng2 = lng1
+            + (bearing.sin() * delta.sin() * lat1.cos())
+                .atan2(delta.cos() - lat1.sin() * lat2.sin());
+
+        LatLng::new(lat2.to_degrees(), normalize_lng(lng2.to_degrees()))
+    }
+
+    /// The half-way point along the great circle to `other`.
+    pub fn midpoint(&self, other: &LatLng) -> LatLng {
+        let (lat1, lat2) = (self.lat.to_radians(), other.lat.to_radians());
+        let lng1 = self.lng.to_radians();
+        let d_lng = (other.lng - self.lng).to_radians();
+
+        let bx = lat2.cos() * d_lng.cos();
+        let by = lat2.cos() * d_lng.sin();
+
+        let lat = (lat1.sin() + lat2.sin()).atan2(((lat1.cos() + bx).powi(2) + by * by).sqrt());
+        let lng = lng1 + by.atan2(lat1.cos() + bx);
+
+        LatLng::new(lat.to_degrees(), normalize_lng(lng.to_degrees()))
+    }
+}
+
+/// Bounds whose southwest longitude is greater than the northeast one cross the antimeridian.
+impl Bounds {
+    pub fn new(southwest: LatLng, northeast: LatLng) -> Bounds {
+        Bounds {
+            northeast,
+            southwest,
+        }
+    }
+
+    /// The smallest bounds containing the circle of `radius` meters around `center`.
+    pub fn from_circle(center: &LatLng, radius: f64) -> Bounds {
+        let delta = (radius / EARTH_RADIUS).to_degrees();
+
+        let north = center.lat + delta;
+        let south = center.lat - delta;
+
+        // the circle reaches a pole, so it spans every longitude.
+        if north >= 90.0 || south <= -90.0 {
+            return Bounds::new(
+                LatLng::new(south.max(-90.0), -180.0),
+                LatLng::new(north.min(90.0), 180.0),
+            );
+        }
+
+        // widest longitude span of the circle, reached at the tangent latitudes.
+        let d_lng = ((radius / EARTH_RADIUS).sin() / center.lat.to_radians().cos())
+            .asin()
+            .to_degrees();
+
+        Bounds::new(
+            LatLng::new(south, normalize_lng(center.lng - d_lng)),
+            LatLng::new(north, normalize_lng(center.lng + d_lng)),
+        )
+    }
+
+    pub fn crosses_antimeridian(&self) -> bool {
+        self.southwest.lng > self.northeast.lng
+    }
+
+    /// Width in degrees of longitude, taking the antimeridian into account.
+    fn lng_span(&self) -> f64 {
+        if self.crosses_antimeridian() {
+            self.northeast.lng - self.southwest.lng + 360.0
+        } else {
+            self.northeast.lng - self.southwest.lng
+        }
+    }
+
+    pub fn contains(&self, point: &LatLng) -> bool {
+        if point.lat < self.southwest.lat || point.lat > self.northeast.lat {
+            return false;
+        }
+
+        if self.crosses_antimeridian() {
+            point.lng >= self.southwest.lng || point.lng <= self.northeast.lng
+        } else {
+            point.lng >= self.southwest.lng && point.lng <= self.northeast.lng
+        }
+    }
+
+    pub fn center(&self) -> LatLng {
+        LatLng::new(
+            (self.southwest.lat + self.northeast.lat) / 2.0,
+            normalize_lng(self.southwest.lng + self.lng_span() / 2.0),
+        )
+    }
+
+    /// Grows the bounds just enough to include `point`,
+    /// crossing the antimeridian when that is the shorter way.
+    pub fn extend(&mut self, point: &LatLng) {
+        self.southwest.lat = self.southwest.lat.min(point.lat);
+        self.northeast.lat = self.northeast.lat.max(point.lat);
+
+        if self.contains(point) {
+            return;
+        }
+
+        let to_west = (self.southwest.lng - point.lng).rem_euclid(360.0);
+        let to_east = (point.lng - self.northeast.lng).rem_euclid(360.0);
+
+        if to_west < to_east {
+            self.southwest.lng = point.lng;
+        } else {
+            self.northeast.lng = point.lng;
+        }
+    }
+
+    /// The smallest bounds containing both `self` and `other`.
+    pub fn union(&self, other: &Bounds) -> Bounds {
+        let mut bounds = Bounds::new(self.southwest, self.northeast);
+
+        bounds.extend(&other.southwest);
+        bounds.extend(&other.northeast);
+
+        bounds
+    }
+
+    /// North-south extent in meters.
+    pub fn height(&self) -> f64 {
+        (self.northeast.lat - self.southwest.lat).to_radians() * EARTH_RADIUS
+    }
+
+    /// East-west extent in meters, measured along the latitude closest to the equator where it is widest.
+    pub fn width(&self) -> f64 {
+        let lat = if self.southwest.lat <= 0.0 && self.northeast.lat >= 0.0 {
+            0.0
+        } else {
+            self.southwest.lat.abs().min(self.northeast.lat.abs())
+        };
+
+        self.lng_span().to_radians() * lat.to_radians().cos() * EARTH_RADIUS
+    }
+
+    /// The radius in meters of the smallest circle around `center` containing the whole bounds.
+    pub fn covering_radius(&self) -> f64 {
+        let center = self.center();
+
+        [
+            self.southwest,
+            self.northeast,
+            LatLng::new(self.southwest.lat, self.northeast.lng),
+            LatLng::new(self.northeast.lat, self.southwest.lng),
+        ]
+        .iter()
+        .map(|corner| center.haversine_distance(corner))
+        .fold(0.0, f64::max)
+    }
+
+    /// Splits the bounds into a grid of `rows` by `cols` cells of equal angular size, row by row from the south-west.
+    pub fn split(&self, rows: usize, cols: usize) -> Vec<Bounds> {
+        let lat_step = (self.northeast.lat - self.southwest.lat) / rows as f64;
+        let lng_step = self.lng_span() / cols as f64;
+
+        (0..rows)
+            .flat_map(|row| (0..cols).map(move |col| (row, col)))
+            .map(|(row, col)| {
+                let south = self.southwest.lat + lat_step * row as f64;
+                let west = self.southwest.lng + lng_step * col as f64;
+
+                Bounds::new(
+                    LatLng::new(south, normalize_lng(west)),
+                    LatLng::new(south + lat_step, normalize_lng(west + lng_step)),
+                )
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::EARTH_RADIUS;
+    use crate::models::{Bounds, LatLng};
+    use crate::testing::assert_close;
+
+    fn dms(degrees: f64, minutes: f64, seconds: f64) -> f64 {
+        degrees.signum() * (degrees.abs() + minutes / 60.0 + seconds / 3600.0)
+    }
+
+    #[test]
+    fn test_haversine_distance() {
+        let one_degree = EARTH_RADIUS.to_radians();
+
+        assert_close(
+            LatLng::new(0.0, 0.0).haversine_distance(&LatLng::new(0.0, 1.0)),
+            one_degree,
+            1e-6,
+        );
+
+        // London to Paris.
+        let london = LatLng::new(51.5074, -0.1278);
+        let paris = LatLng::new(48.8566, 2.3522);
+        assert_close(london.haversine_distance(&paris), 343_556.0, 50.0);
+    }
+
+    #[test]
+    fn test_vincenty_distance() {
+        // Flinders Peak to Buninyong, the reference example from Vincenty's paper.
+        let flinders_peak = LatLng::new(dms(-37.0, 57.0, 3.72030), dms(144.0, 25.0, 29.52440));
+        let buninyong = LatLng::new(dms(-37.0, 39.0, 10.15610), dms(143.0, 55.0, 35.38390));
+
+        let distance = flinders_peak.vincenty_distance(&buninyong).unwrap();
+        assert_close(distance, 54_972.271, 1e-3);
+
+        assert_eq!(flinders_peak.vincenty_distance(&flinders_peak), Some(0.0));
+        assert_eq!(
+            LatLng::new(0.0, 0.0).vincenty_distance(&LatLng::new(0.5, 179.7)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_initial_bearing() {
+        let origin = LatLng::new(0.0, 0.0);
+
+        assert_close(origin.initial_bearing(&LatLng::new(1.0, 0.0)), 0.0, 1e-9);
+        assert_close(origin.initial_bearing(&LatLng::new(0.0, 1.0)), 90.0, 1e-9);
+        assert_close(origin.initial_bearing(&LatLng::new(-1.0, 0.0)), 180.0, 1e-9);
+        assert_close(origin.initial_bearing(&LatLng::new(0.0, -1.0)), 270.0, 1e-9);
+
+        // Flinders Peak to Buninyong on a sphere, 306°52'05" on the ellipsoid.
+        let flinders_peak = LatLng::new(dms(-37.0, 57.0, 3.72030), dms(144.0, 25.0, 29.52440));
+        let buninyong = LatLng::new(dms(-37.0, 39.0, 10.15610), dms(143.0, 55.0, 35.38390));
+        assert_close(
+            flinders_peak.initial_bearing(&buninyong),
+            dms(306.0, 52.0, 5.37),
+            0.2,
+        );
+    }
+
+    #[test]
+    fn test_destination() {
+        let origin = LatLng::new(0.0, 0.0);
+        let one_degree = EARTH_RADIUS.to_radians();
+
+        let east = origin.destination(90.0, one_degree);
+        assert_close(east.lat, 0.0, 1e-9);
+        assert_close(east.lng, 1.0, 1e-9);
+
+        let london = LatLng::new(51.5074, -0.1278);
+        let paris = LatLng::new(48.8566, 2.3522);
+        let reached = london.destination(
+            london.initial_bearing(&paris),
+            london.haversine_distance(&paris),
+        );
+        assert_close(reached.lat, paris.lat, 1e-9);
+        assert_close(reached.lng, paris.lng, 1e-9);
+
+        let across = LatLng::new(0.0, 179.5).destination(90.0, one_degree);
+        assert_close(across.lng, -179.5, 1e-9);
+    }
+
+    #[test]
+    fn test_midpoint() {
+        let midpoint = LatLng::new(0.0, 0.0).midpoint(&LatLng::new(0.0, 90.0));
+        assert_close(midpoint.lat, 0.0, 1e-9);
+        assert_close(midpoint.lng, 45.0, 1e-9);
+
+        let london = LatLng::new(51.5074, -0.1278);
+        let paris = LatLng::new(48.8566, 2.3522);
+        let midpoint = london.midpoint(&paris);
+        assert_close(
+            london.haversine_distance(&midpoint),
+            paris.haversine_distance(&midpoint),
+            1e-6,
+        );
+    }
+
+    #[test]
+    fn test_bounds_contains() {
+        let bounds = Bounds::new(LatLng::new(-34.0, 151.0), LatLng::new(-33.0, 152.0));
+
+        assert!(bounds.contains(&LatLng::new(-33.5, 151.5)));
+        assert!(bounds.contains(&LatLng::new(-34.0, 151.0)));
+        assert!(!bounds.contains(&LatLng::new(-32.9, 151.5)));
+        assert!(!bounds.contains(&LatLng::new(-33.5, 150.9)));
+
+        let pacific = Bounds::new(LatLng::new(-10.0, 170.0), LatLng::new(10.0, -170.0));
+        assert!(pacific.crosses_antimeridian());
+        assert!(pacific.contains(&LatLng::new(0.0, 179.0)));
+        assert!(pacific.contains(&LatLng::new(0.0, -175.0)));
+        assert!(!pacific.contains(&LatLng::new(0.0, 0.0)));
+    }
+
+    #[test]
+    fn test_bounds_center() {
+        let bounds = Bounds::new(LatLng::new(-34.0, 151.0), LatLng::new(-33.0, 152.0));
+        assert_eq!(bounds.center(), LatLng::new(-33.5, 151.5));
+
+        let pacific = Bounds::new(LatLng::new(-10.0, 170.0), LatLng::new(10.0, -170.0));
+        assert_eq!(pacific.center(), LatLng::new(0.0, -180.0));
+    }
+
+    #[test]
+    fn test_bounds_extend_and_union() {
+        let mut bounds = Bounds::new(LatLng::new(0.0, 0.0), LatLng::new(1.0, 1.0));
+
+        bounds.extend(&LatLng::new(2.0, -1.0));
+        assert_eq!(
+            bounds,
+            Bounds::new(LatLng::new(0.0, -1.0), LatLng::new(2.0, 1.0))
+        );
+
+        let mut pacific = Bounds::new(LatLng::new(0.0, 175.0), LatLng::new(1.0, 179.0));
+        pacific.extend(&LatLng::new(0.5, -178.0));
+        assert!(pacific.crosses_antimeridian());
+        assert_eq!(pacific.northeast.lng, -178.0);
+
+        let union = Bounds::new(LatLng::new(0.0, 0.0), LatLng::new(1.0, 1.0))
+            .union(&Bounds::new(LatLng::new(-1.0, 2.0), LatLng::new(0.5, 3.0)));
+        assert_eq!(
+            union,
+            Bounds::new(LatLng::new(-1.0, 0.0), LatLng::new(1.0, 3.0))
+        );
+    }
+
+    #[test]
+    fn test_bounds_from_circle() {
+        let center = LatLng::new(-33.8688, 151.2093);
+        let bounds = Bounds::from_circle(&center, 1000.0);
+
+        assert!(bounds.contains(&center));
+        assert_close(
+            center.haversine_distance(&LatLng::new(bounds.northeast.lat, center.lng)),
+            1000.0,
+            1e-6,
+        );
+
+        // every point of the circle lies inside.
+        for bearing in (0..360).step_by(5) {
+            let edge = center.destination(bearing as f64, 999.999);
+            assert!(bounds.contains(&edge), "bearing {}", bearing);
+        }
+
+        let polar = Bounds::from_circle(&LatLng::new(89.99, 0.0), 5000.0);
+        assert_eq!(polar.northeast.lat, 90.0);
+        assert_eq!(polar.southwest.lng, -180.0);
+    }
+
+    #[test]
+    fn test_bounds_size() {
+        let bounds = Bounds::new(LatLng::new(0.0, 0.0), LatLng::new(1.0, 2.0));
+        let one_degree = EARTH_RADIUS.to_radians();
+
+        assert_close(bounds.height(), one_degree, 1e-6);
+        assert_close(bounds.width(), 2.0 * one_degree, 1e-6);
+
+        // the southern corners are the farthest, being closer to the equator.
+        assert_close(
+            bounds.covering_radius(),
+            bounds.center().haversine_distance(&bounds.southwest),
+            1e-6,
+        );
+    }
+
+    #[test]
+    fn test_bounds_split() {
+        let bounds = Bounds::new(LatLng::new(0.0, 170.0), LatLng::new(2.0, -170.0));
+        let cells = bounds.split(2, 2);
+
+        assert_eq!(cells.len(), 4);
+        assert_eq!(
+            cells[0],
+            Bounds::new(LatLng::new(0.0, 170.0), LatLng::new(1.0, -180.0))
+        );
+        assert_eq!(
+            cells[3],
+            Bounds::new(LatLng::new(1.0, -180.0), LatLng::new(2.0, -170.0))
+        );
+    }
+}
@@ -0,0 +1,319 @@
+use crate::models::{Error, Place};
+use std::collections::{BTreeMap, HashMap};
+
+/// Invokes `$macro!($args; field, ...)` with every `Option` field of `Place`, checked by `destructure!`.
+macro_rules! with_optional_fields {
+    ($macro:ident!($($args:tt)*)) => {
+        $macro!(
+            $($args)*;
+            address_components,
+            adr_address,
+            business_status,
+            curbside_pickup,
+            current_opening_hours,
+            delivery,
+            dine_in,
+            editorial_summary,
+            formatted_address,
+            formatted_phone_number,
+            geometry,
+            icon,
+            icon_background_color,
+            icon_mask_base_uri,
+            international_phone_number,
+            name,
+            opening_hours,
+            permanently_closed,
+            photos,
+            place_id,
+            plus_code,
+            price_level,
+            rating,
+            reference,
+            reservable,
+            reviews,
+            scope,
+            secondary_opening_hours,
+            serves_beer,
+            serves_breakfast,
+            serves_brunch,
+            serves_dinner,
+            serves_lunch,
+            serves_vegetarian_food,
+            serves_wine,
+            takeout,
+            types,
+            url,
+            user_ratings_total,
+            utc_offset,
+            vicinity,
+            website,
+            wheelchair_accessible_entrance
+        )
+    };
+}
+
+/// Destructures `Place` without a rest pattern, so that a field missing from `with_optional_fields!`
+/// fails to compile.
+macro_rules! destructure {
+    ($place:expr; $($field:ident),*) => {
+        let Place { $($field: _,)* extra: _ } = $place;
+    };
+}
+
+const _: fn(Place) = |place| {
+    with_optional_fields!(destructure!(place));
+};
+
+macro_rules! fill_missing {
+    ($target:expr, $source:expr, $filled:expr; $($field:ident),*) => {
+        $(
+            if $target.$field.is_none() && $source.$field.is_some() {
+                $target.$field = $source.$field.take();
+                $filled.push(stringify!($field).to_owned());
+            }
+        )*
+    };
+}
+
+macro_rules! populated {
+    ($place:expr, $fields:expr; $($field:ident),*) => {
+        $(
+            if $place.$field.is_some() {
+                $fields.push(stringify!($field).to_owned());
+            }
+        )*
+    };
+}
+
+impl Place {
+    /// Names of the fields this record has a value for, unmodeled fields under their JSON key.
+    pub fn populated_fields(&self) -> Vec<String> {
+        let mut fields = vec![];
+
+        with_optional_fields!(populated!(self, fields));
+
+        fields.extend(self.extra.keys().cloned());
+
+        fields
+    }
+
+    /// Fills every missing field from `other`, a record of the same place from another search,
+    /// and returns the names of the fields it filled. Fields this record already has are kept.
+    pub fn merge(&mut self, mut other: Place) -> Result<Vec<String>, Error> {
+        if let (Some(a), Some(b)) = (&self.place_id, &other.place_id) {
+            if a != b {
+                return Err(format!("cannot merge place {} into place {}", b, a).into());
+            }
+        }
+
+        let mut filled = vec![];
+
+        with_optional_fields!(fill_missing!(self, other, filled));
+
+        for (key, value) in other.extra {
+            if !self.extra.contains_key(&key) {
+                filled.push(key.clone());
+                self.extra.insert(key, value);
+            }
+        }
+
+        Ok(filled)
+    }
+}
+
+/// A place merged from several records, and which search provided each of its fields.
+#[derive(Debug)]
+pub struct MergedPlace {
+    pub place: Place,
+
+    /// The source each populated field came from, by field name.
+    pub provenance: BTreeMap<String, String>,
+}
+
+/// Places from several searches, one entry per place_id.
+///
+/// Records without a place_id cannot be matched, and are kept as entries of their own.
+#[derive(Debug, Default)]
+pub struct PlaceCollection {
+    entries: Vec<MergedPlace>,
+    index: HashMap<String, usize>,
+}
+
+impl PlaceCollection {
+    pub fn new() -> PlaceCollection {
+        PlaceCollection::default()
+    }
+
+    /// Adds a record produced by `source`, such as "find", "nearby" or "details",
+    /// merging it into the entry with the same place_id. Returns whether the place was new.
+    pub fn insert(&mut self, place: Place, source: impl Into<String>) -> bool {
+        let source = source.into();
+
+        if let Some(&position) = place
+            .place_id
+            .as_ref()
+            .and_then(|place_id| self.index.get(place_id))
+        {
+            let entry = &mut self.entries[position];
+            let filled = entry
+                .place
+                .merge(place)
+                .expect("entries are indexed by place_id");
+
+            for field in filled {
+                entry.provenance.insert(field, source.clone());
+            }
+
+            return false;
+        }
+
+        if let Some(place_id) = &place.place_id {
+            self.index.insert(place_id.clone(), self.entries.len());
+        }
+
+        let provenance = place
+            .populated_fields()
+            .into_iter()
+            .map(|field| (field, source.clone()))
+            .collect();
+
+        self.entries.push(MergedPlace { place, provenance });
+
+        true
+    }
+
+    /// Adds every record produced by `source`.
+    pub fn extend(&mut self, places: impl IntoIterator<Item = Place>, source: &str) {
+        for place in places {
+            self.insert(place, source);
+        }
+    }
+
+    pub fn get(&self, place_id: &str) -> Option<&MergedPlace> {
+        self.index
+            .get(place_id)
+            .map(|&position| &self.entries[position])
+    }
+
+    /// Entries in the order their places were first seen.
+    pub fn iter(&self) -> impl Iterator<Item = &MergedPlace> {
+        self.entries.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn into_places(self) -> Vec<Place> {
+        self.entries.into_iter().map(|entry| entry.place).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PlaceCollection;
+    use crate::models::Place;
+    use serde_json::json;
+
+    fn place(value: serde_json::Value) -> Place {
+        serde_json::from_value(value).unwrap()
+    }
+
+    fn nearby() -> Place {
+        place(json!({
+            "place_id": "ChIJ3S-JXmauEmsRUcIaWtf4MzE",
+            "name": "Sydney Opera House",
+            "rating": 4.7,
+            "vicinity": "Bennelong Point, Sydney",
+            "icon_background_color": "#7B9EB0"
+        }))
+    }
+
+    fn details() -> Place {
+        place(json!({
+            "place_id": "ChIJ3S-JXmauEmsRUcIaWtf4MzE",
+            "name": "Opera House",
+            "formatted_phone_number": "(02) 9250 7111",
+            "website": "https://www.sydneyoperahouse.com/",
+            "wheelchair_accessible_entrance": true,
+            "curbside_pickup": false,
+            "primary_type": "performing_arts_theater"
+        }))
+    }
+
+    #[test]
+    fn test_merge_fills_missing_fields() {
+        let mut merged = nearby();
+        let filled = merged.merge(details()).unwrap();
+
+        assert_eq!(
+            filled,
+            vec![
+                "curbside_pickup",
+                "formatted_phone_number",
+                "website",
+                "wheelchair_accessible_entrance",
+                "primary_type"
+            ]
+        );
+        assert_eq!(merged.name.as_deref(), Some("Sydney Opera House"));
+        assert_eq!(merged.rating, Some(4.7));
+        assert_eq!(
+            merged.website.as_deref(),
+            Some("https://www.sydneyoperahouse.com/")
+        );
+        assert_eq!(merged.curbside_pickup, Some(false));
+        assert_eq!(merged.icon_background_color.as_deref(), Some("#7B9EB0"));
+        assert_eq!(merged.extra["primary_type"], "performing_arts_theater");
+    }
+
+    #[test]
+    fn test_merge_rejects_other_place() {
+        let mut merged = nearby();
+        let other = place(json!({ "place_id": "another", "website": "https://example.com" }));
+
+        assert!(merged.merge(other).is_err());
+        assert_eq!(merged.website, None);
+    }
+
+    #[test]
+    fn test_collection_dedupes_with_provenance() {
+        let mut places = PlaceCollection::new();
+
+        assert!(places.insert(nearby(), "nearby"));
+        assert!(!places.insert(details(), "details"));
+        places.extend(
+            vec![
+                place(json!({ "name": "Unknown kiosk" })),
+                place(json!({ "name": "Unknown kiosk" })),
+            ],
+            "find",
+        );
+
+        assert_eq!(places.len(), 3);
+
+        let opera = places.get("ChIJ3S-JXmauEmsRUcIaWtf4MzE").unwrap();
+        assert_eq!(opera.provenance["name"], "nearby");
+        assert_eq!(opera.provenance["rating"], "nearby");
+        assert_eq!(opera.provenance["icon_background_color"], "nearby");
+        assert_eq!(opera.provenance["website"], "details");
+        assert_eq!(opera.provenance["curbside_pickup"], "details");
+        assert_eq!(opera.provenance["primary_type"], "details");
+        assert!(!opera.provenance.contains_key("reviews"));
+
+        let names = places
+            .into_places()
+            .into_iter()
+            .map(|place| place.name.unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            vec!["Sydney Opera House", "Unknown kiosk", "Unknown kiosk"]
+        );
+    }
+}
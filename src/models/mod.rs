@@ -1,12 +1,26 @@
+mod business_status;
+mod coordinates;
+mod distance;
+mod geodesy;
+mod merge;
+mod opening_hours;
 mod place;
+mod polygon;
 mod search_status;
 
+pub use business_status::*;
+pub use coordinates::*;
+pub use distance::*;
+pub use geodesy::*;
+pub use merge::*;
+pub use opening_hours::*;
 pub use place::*;
+pub use polygon::*;
 pub use search_status::*;
 
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Serialize, Deserialize, Default, PartialEq)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq)]
 pub struct LatLng {
     pub lat: f64,
     pub lng: f64,
@@ -0,0 +1,661 @@
+use chrono::{
+    DateTime, Datelike, Duration, FixedOffset, NaiveDate, NaiveTime, TimeZone, Timelike, Weekday,
+};
+use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
+use std::ops::Range;
+
+const MINUTES_PER_DAY: u32 = 24 * 60;
+const MINUTES_PER_WEEK: u32 = 7 * MINUTES_PER_DAY;
+
+/// Google numbers the days of the week from 0 to 6, starting on Sunday.
+const WEEKDAYS: [Weekday; 7] = [
+    Weekday::Sun,
+    Weekday::Mon,
+    Weekday::Tue,
+    Weekday::Wed,
+    Weekday::Thu,
+    Weekday::Fri,
+    Weekday::Sat,
+];
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "RawPeriodDetail", into = "RawPeriodDetail")]
+pub struct PlaceOpeningHoursPeriodDetail {
+    /// The day of the week.
+    pub day: Weekday,
+
+    /// The time of day, in the local timezone for the place.
+    pub time: NaiveTime,
+
+    /// A date in the local timezone for the place, for example 2010-12-31.
+    pub date: Option<NaiveDate>,
+
+    /// True if a given period was truncated due to a seven-day cutoff.
+    pub truncated: Option<bool>,
+}
+
+/// The shape Google uses on the wire: a 0–6 day and an hhmm time string.
+#[derive(Serialize, Deserialize)]
+struct RawPeriodDetail {
+    day: u8,
+    time: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    date: Option<NaiveDate>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    truncated: Option<bool>,
+}
+
+impl TryFrom<RawPeriodDetail> for PlaceOpeningHoursPeriodDetail {
+    type Error = String;
+
+    fn try_from(raw: RawPeriodDetail) -> Result<Self, Self::Error> {
+        let day = *WEEKDAYS
+            .get(raw.day as usize)
+            .ok_or_else(|| format!("invalid day {}, expected 0-6", raw.day))?;
+
+        let invalid_time = || format!("invalid time {:?}, expected hhmm", raw.time);
+
+        if raw.time.len() != 4 || !raw.time.bytes().all(|byte| byte.is_ascii_digit()) {
+            return Err(invalid_time());
+        }
+
+        let hour: u32 = raw.time[..2].parse().map_err(|_| invalid_time())?;
+        let minute: u32 = raw.time[2..].parse().map_err(|_| invalid_time())?;
+
+        // Some places report closing at "2400", which is midnight of the following day.
+        if hour == 24 && minute == 0 {
+            return Ok(PlaceOpeningHoursPeriodDetail {
+                day: day.succ(),
+                time: NaiveTime::MIN,
+                date: raw.date.and_then(|date| date.succ_opt()),
+                truncated: raw.truncated,
+            });
+        }
+
+        let time = NaiveTime::from_hms_opt(hour, minute, 0).ok_or_else(invalid_time)?;
+
+        Ok(PlaceOpeningHoursPeriodDetail {
+            day,
+            time,
+            date: raw.date,
+            truncated: raw.truncated,
+        })
+    }
+}
+
+impl From<PlaceOpeningHoursPeriodDetail> for RawPeriodDetail {
+    fn from(detail: PlaceOpeningHoursPeriodDetail) -> Self {
+        RawPeriodDetail {
+            day: detail.day.num_days_from_sunday() as u8,
+            time: detail.time.format("%H%M").to_string(),
+            date: detail.date,
+            truncated: detail.truncated,
+        }
+    }
+}
+
+impl PlaceOpeningHoursPeriodDetail {
+    /// Minutes elapsed since Sunday 00:00.
+    pub fn minute_of_week(&self) -> u32 {
+        self.day.num_days_from_sunday() * MINUTES_PER_DAY
+            + self.time.hour() * 60
+            + self.time.minute()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlaceOpeningHoursPeriod {
+    /// When the place opens.
+    pub open: PlaceOpeningHoursPeriodDetail,
+
+    /// When the place closes.
+    /// Google omits it for places that are always open, reported as a single Sunday 0000 open.
+    pub close: Option<PlaceOpeningHoursPeriodDetail>,
+}
+
+impl PlaceOpeningHoursPeriod {
+    /// Whether this period describes a place that never closes.
+    pub fn is_always_open(&self) -> bool {
+        self.close.is_none()
+    }
+
+    /// How long the place stays open, wrapping past Saturday midnight when needed.
+    /// Returns `None` for places that never close.
+    pub fn duration(&self) -> Option<Duration> {
+        let close = self.close.as_ref()?;
+
+        let minutes = match (close.minute_of_week() + MINUTES_PER_WEEK - self.open.minute_of_week())
+            % MINUTES_PER_WEEK
+        {
+            0 => MINUTES_PER_WEEK,
+            minutes => minutes,
+        };
+
+        Some(Duration::minutes(minutes as i64))
+    }
+
+    /// Whether the period closes on a later day than it opens, e.g. Friday 22:00 – Saturday 02:00.
+    pub fn crosses_midnight(&self) -> bool {
+        match self.duration() {
+            Some(duration) => {
+                let opened = self.open.time.hour() * 60 + self.open.time.minute();
+
+                opened as i64 + duration.num_minutes() > MINUTES_PER_DAY as i64
+            }
+            None => true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlaceSpecialDay {
+    /// A date expressed in RFC3339 format in the local timezone for the place, for example 2010-12-31.
+    pub date: Option<NaiveDate>,
+
+    /// True if there are exceptional hours on this day,
+    /// in which case `periods` of current_opening_hours differ from the regular ones.
+    pub exceptional_hours: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlaceOpeningHours {
+    /// A boolean value indicating if the place is open at the current time.
+    pub open_now: Option<bool>,
+
+    /// An array of opening periods covering seven days, starting from Sunday, in chronological order.
+    pub periods: Option<Vec<PlaceOpeningHoursPeriod>>,
+
+    /// An array of up to seven entries corresponding to the next seven days.
+    /// Only set for current_opening_hours and secondary_opening_hours.
+    pub special_days: Option<Vec<PlaceSpecialDay>>,
+
+    /// A type string used to identify the type of secondary hours (for example, DRIVE_THROUGH, HAPPY_HOUR).
+    /// Only set for secondary_opening_hours.
+    #[serde(rename = "type")]
+    pub hours_type: Option<String>,
+
+    /// An array of strings describing in human-readable text the hours of the place.
+    pub weekday_text: Option<Vec<String>>,
+}
+
+/// Local opening intervals, each running from an opening to the following closing.
+pub type OpeningIntervals = Vec<Range<DateTime<FixedOffset>>>;
+
+/// `date` at `time` in the given offset.
+fn local(date: NaiveDate, time: NaiveTime, offset: &FixedOffset) -> DateTime<FixedOffset> {
+    offset.from_local_datetime(&date.and_time(time)).unwrap()
+}
+
+/// Sorts the intervals and joins those that overlap or touch.
+fn merge_intervals<T: Ord + Copy>(mut intervals: Vec<Range<T>>) -> Vec<Range<T>> {
+    intervals.sort_by_key(|interval| interval.start);
+
+    let mut merged: Vec<Range<T>> = vec![];
+
+    for interval in intervals {
+        if let Some(last) = merged.last_mut() {
+            if interval.start <= last.end {
+                last.end = last.end.max(interval.end);
+
+                continue;
+            }
+        }
+
+        merged.push(interval);
+    }
+
+    merged
+}
+
+/// Opening-hours queries built on top of the effective hours of each calendar date.
+pub(crate) struct Schedule<F>
+where
+    F: Fn(NaiveDate) -> Option<OpeningIntervals>,
+{
+    pub hours_on: F,
+}
+
+impl<F> Schedule<F>
+where
+    F: Fn(NaiveDate) -> Option<OpeningIntervals>,
+{
+    /// Opening intervals overlapping `from..=to`, not clipped to it.
+    /// Looks a week back so that places opened for days on end are caught as well.
+    fn intervals_between(
+        &self,
+        from: &DateTime<FixedOffset>,
+        to: &DateTime<FixedOffset>,
+    ) -> Option<OpeningIntervals> {
+        let mut intervals = vec![];
+        let mut date = from.date_naive() - Duration::weeks(1);
+
+        while date <= to.date_naive() {
+            intervals.extend((self.hours_on)(date)?);
+
+            date = date.succ_opt()?;
+        }
+
+        Some(
+            merge_intervals(intervals)
+                .into_iter()
+                .filter(|interval| interval.end > *from && interval.start <= *to)
+                .collect(),
+        )
+    }
+
+    pub fn is_open_at(&self, instant: &DateTime<FixedOffset>) -> Option<bool> {
+        let intervals = self.intervals_between(instant, instant)?;
+
+        Some(intervals.iter().any(|interval| interval.contains(instant)))
+    }
+
+    pub fn next_open_after(
+        &self,
+        instant: &DateTime<FixedOffset>,
+    ) -> Option<DateTime<FixedOffset>> {
+        let until = *instant + Duration::weeks(1) + Duration::days(1);
+
+        self.intervals_between(instant, &until)?
+            .into_iter()
+            .map(|interval| interval.start)
+            .find(|start| start > instant)
+    }
+
+    pub fn next_close_after(
+        &self,
+        instant: &DateTime<FixedOffset>,
+    ) -> Option<DateTime<FixedOffset>> {
+        let until = *instant + Duration::weeks(1) + Duration::days(1);
+
+        // staying open past the horizon means the place never closes.
+        self.intervals_between(instant, &until)?
+            .into_iter()
+            .map(|interval| interval.end)
+            .find(|end| end > instant)
+            .filter(|end| *end <= until)
+    }
+
+    pub fn open_intervals_in(
+        &self,
+        range: Range<DateTime<FixedOffset>>,
+    ) -> Option<OpeningIntervals> {
+        let intervals = self.intervals_between(&range.start, &range.end)?;
+
+        Some(
+            intervals
+                .into_iter()
+                .map(|interval| interval.start.max(range.start)..interval.end.min(range.end))
+                .filter(|interval| !interval.is_empty())
+                .collect(),
+        )
+    }
+}
+
+/// All the offline queries interpret instants in the offset they carry,
+/// which should be the place's own offset, see `Place::local_offset`.
+/// They return `None` when Google did not send any `periods`.
+impl PlaceOpeningHours {
+    /// Opening periods as minutes since Sunday 00:00, merged and possibly running past the end of the week.
+    fn weekly_intervals(&self) -> Option<Vec<Range<u32>>> {
+        let periods = self.periods.as_ref()?;
+
+        let intervals = periods
+            .iter()
+            .map(|period| {
+                let start = period.open.minute_of_week();
+
+                match period.duration() {
+                    Some(duration) => start..start + duration.num_minutes() as u32,
+                    None => 0..MINUTES_PER_WEEK,
+                }
+            })
+            .collect();
+
+        Some(merge_intervals(intervals))
+    }
+
+    /// Whether the periods cover the whole week without a single closing minute.
+    pub fn is_always_open(&self) -> Option<bool> {
+        let intervals = self.weekly_intervals()?;
+
+        let covered: u32 = merge_intervals(
+            intervals
+                .into_iter()
+                .flat_map(|interval| {
+                    // fold the part running past Saturday back onto the start of the week.
+                    let wrapped = interval.end.saturating_sub(MINUTES_PER_WEEK);
+
+                    vec![
+                        interval.start..interval.end.min(MINUTES_PER_WEEK),
+                        0..wrapped,
+                    ]
+                })
+                .filter(|interval| !interval.is_empty())
+                .collect(),
+        )
+        .iter()
+        .map(|interval| interval.end - interval.start)
+        .sum();
+
+        Some(covered >= MINUTES_PER_WEEK)
+    }
+
+    /// Whether `special_days` flags `date` as having hours that differ from the regular ones.
+    pub fn has_exceptional_hours_on(&self, date: NaiveDate) -> bool {
+        self.special_days.iter().flatten().any(|special_day| {
+            special_day.date == Some(date) && special_day.exceptional_hours.unwrap_or(true)
+        })
+    }
+
+    /// The intervals opening on `date`, which may run into the following days.
+    /// Special days use the periods Google dated for them, and have no hours at all when there are none;
+    /// any other date falls back to the regular weekly periods.
+    pub fn hours_on(&self, date: NaiveDate, offset: &FixedOffset) -> Option<OpeningIntervals> {
+        if self.has_exceptional_hours_on(date) {
+            let periods = self.periods.as_ref()?;
+
+            let intervals = periods
+                .iter()
+                .filter(|period| period.open.date == Some(date))
+                .map(|period| {
+                    let start = local(date, period.open.time, offset);
+
+                    start..start + period.duration().unwrap_or_else(|| Duration::days(1))
+                })
+                .collect();
+
+            return Some(intervals);
+        }
+
+        let day = date.weekday().num_days_from_sunday();
+        let midnight = local(date, NaiveTime::MIN, offset);
+
+        let intervals = self
+            .weekly_intervals()?
+            .into_iter()
+            .filter(|interval| interval.start / MINUTES_PER_DAY == day)
+            .map(|interval| {
+                let start = midnight + Duration::minutes((interval.start % MINUTES_PER_DAY) as i64);
+
+                start..start + Duration::minutes((interval.end - interval.start) as i64)
+            })
+            .collect();
+
+        Some(intervals)
+    }
+
+    fn schedule(
+        &self,
+        offset: FixedOffset,
+    ) -> Schedule<impl Fn(NaiveDate) -> Option<OpeningIntervals> + '_> {
+        Schedule {
+            hours_on: move |date| self.hours_on(date, &offset),
+        }
+    }
+
+    /// Whether the place is open at `instant`.
+    pub fn is_open_at(&self, instant: &DateTime<FixedOffset>) -> Option<bool> {
+        self.schedule(*instant.offset()).is_open_at(instant)
+    }
+
+    /// The first time strictly after `instant` at which the place opens.
+    /// Returns `None` as well for places that never close, and so never open either.
+    pub fn next_open_after(
+        &self,
+        instant: &DateTime<FixedOffset>,
+    ) -> Option<DateTime<FixedOffset>> {
+        self.schedule(*instant.offset()).next_open_after(instant)
+    }
+
+    /// The first time strictly after `instant` at which the place closes.
+    /// Returns `None` as well for places that never close.
+    pub fn next_close_after(
+        &self,
+        instant: &DateTime<FixedOffset>,
+    ) -> Option<DateTime<FixedOffset>> {
+        self.schedule(*instant.offset()).next_close_after(instant)
+    }
+
+    /// The intervals within `range` during which the place is open, clipped to `range`.
+    pub fn open_intervals_in(
+        &self,
+        range: Range<DateTime<FixedOffset>>,
+    ) -> Option<OpeningIntervals> {
+        self.schedule(*range.start.offset())
+            .open_intervals_in(range)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PlaceOpeningHours, PlaceOpeningHoursPeriod};
+    use chrono::{DateTime, Duration, FixedOffset, NaiveDate, NaiveTime, Weekday};
+
+    fn at(date: &str) -> DateTime<FixedOffset> {
+        DateTime::parse_from_rfc3339(date).unwrap()
+    }
+
+    /// Open Monday to Friday 09:00 - 17:00, and Saturday 22:00 until Sunday 02:00.
+    fn office_hours() -> PlaceOpeningHours {
+        let periods = (1..=5)
+            .map(|day| {
+                format!(
+                    r#"{{ "open": {{ "day": {0}, "time": "0900" }}, "close": {{ "day": {0}, "time": "1700" }} }}"#,
+                    day
+                )
+            })
+            .chain(std::iter::once(
+                r#"{ "open": { "day": 6, "time": "2200" }, "close": { "day": 0, "time": "0200" } }"#
+                    .to_owned(),
+            ))
+            .collect::<Vec<_>>()
+            .join(",");
+
+        serde_json::from_str(&format!(r#"{{ "periods": [{}] }}"#, periods)).unwrap()
+    }
+
+    fn always_open() -> PlaceOpeningHours {
+        serde_json::from_str(r#"{ "periods": [{ "open": { "day": 0, "time": "0000" } }] }"#)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_is_open_at() {
+        let hours = office_hours();
+
+        // 2022-10-24 is a Monday.
+        assert_eq!(
+            hours.is_open_at(&at("2022-10-24T08:59:00+10:00")),
+            Some(false)
+        );
+        assert_eq!(
+            hours.is_open_at(&at("2022-10-24T09:00:00+10:00")),
+            Some(true)
+        );
+        assert_eq!(
+            hours.is_open_at(&at("2022-10-24T16:59:00+10:00")),
+            Some(true)
+        );
+        assert_eq!(
+            hours.is_open_at(&at("2022-10-24T17:00:00+10:00")),
+            Some(false)
+        );
+    }
+
+    #[test]
+    fn test_is_open_across_sunday_midnight() {
+        let hours = office_hours();
+
+        assert_eq!(
+            hours.is_open_at(&at("2022-10-29T23:30:00+10:00")),
+            Some(true)
+        );
+        assert_eq!(
+            hours.is_open_at(&at("2022-10-30T01:30:00+10:00")),
+            Some(true)
+        );
+        assert_eq!(
+            hours.is_open_at(&at("2022-10-30T02:00:00+10:00")),
+            Some(false)
+        );
+    }
+
+    #[test]
+    fn test_next_open_and_close() {
+        let hours = office_hours();
+
+        let friday_evening = at("2022-10-28T18:00:00+10:00");
+        assert_eq!(
+            hours.next_open_after(&friday_evening),
+            Some(at("2022-10-29T22:00:00+10:00"))
+        );
+        assert_eq!(
+            hours.next_close_after(&friday_evening),
+            Some(at("2022-10-30T02:00:00+10:00"))
+        );
+
+        let monday_noon = at("2022-10-24T12:00:00+10:00");
+        assert_eq!(
+            hours.next_open_after(&monday_noon),
+            Some(at("2022-10-25T09:00:00+10:00"))
+        );
+        assert_eq!(
+            hours.next_close_after(&monday_noon),
+            Some(at("2022-10-24T17:00:00+10:00"))
+        );
+    }
+
+    #[test]
+    fn test_open_intervals_in() {
+        let hours = office_hours();
+
+        let intervals = hours
+            .open_intervals_in(at("2022-10-28T12:00:00+10:00")..at("2022-10-31T10:00:00+10:00"))
+            .unwrap();
+
+        assert_eq!(
+            intervals,
+            vec![
+                at("2022-10-28T12:00:00+10:00")..at("2022-10-28T17:00:00+10:00"),
+                at("2022-10-29T22:00:00+10:00")..at("2022-10-30T02:00:00+10:00"),
+                at("2022-10-31T09:00:00+10:00")..at("2022-10-31T10:00:00+10:00"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_always_open_queries() {
+        let hours = always_open();
+        let instant = at("2022-10-26T03:00:00-04:00");
+
+        assert_eq!(hours.is_always_open(), Some(true));
+        assert_eq!(hours.is_open_at(&instant), Some(true));
+        assert_eq!(hours.next_open_after(&instant), None);
+        assert_eq!(hours.next_close_after(&instant), None);
+        assert_eq!(
+            hours.open_intervals_in(instant..instant + Duration::days(10)),
+            Some(vec![instant..instant + Duration::days(10)])
+        );
+    }
+
+    #[test]
+    fn test_queries_without_periods() {
+        let hours: PlaceOpeningHours = serde_json::from_str(r#"{ "open_now": true }"#).unwrap();
+
+        assert_eq!(hours.is_open_at(&at("2022-10-26T03:00:00Z")), None);
+        assert_eq!(hours.is_always_open(), None);
+    }
+
+    #[test]
+    fn test_always_open() {
+        let hours: PlaceOpeningHours = serde_json::from_str(
+            r#"{ "open_now": true, "periods": [{ "open": { "day": 0, "time": "0000" } }] }"#,
+        )
+        .unwrap();
+
+        let periods = hours.periods.unwrap();
+        assert_eq!(periods.len(), 1);
+        assert!(periods[0].is_always_open());
+        assert_eq!(periods[0].open.day, Weekday::Sun);
+        assert_eq!(periods[0].duration(), None);
+    }
+
+    #[test]
+    fn test_midnight_crossing_period() {
+        let period: PlaceOpeningHoursPeriod = serde_json::from_str(
+            r#"{ "open": { "day": 5, "time": "2200" }, "close": { "day": 6, "time": "0200" } }"#,
+        )
+        .unwrap();
+
+        assert_eq!(period.open.day, Weekday::Fri);
+        assert_eq!(period.open.time, NaiveTime::from_hms_opt(22, 0, 0).unwrap());
+        assert_eq!(period.duration(), Some(Duration::hours(4)));
+        assert!(period.crosses_midnight());
+    }
+
+    #[test]
+    fn test_period_wrapping_past_saturday() {
+        let period: PlaceOpeningHoursPeriod = serde_json::from_str(
+            r#"{ "open": { "day": 6, "time": "2000" }, "close": { "day": 0, "time": "0300" } }"#,
+        )
+        .unwrap();
+
+        assert_eq!(period.duration(), Some(Duration::hours(7)));
+        assert!(period.crosses_midnight());
+    }
+
+    #[test]
+    fn test_same_day_period() {
+        let period: PlaceOpeningHoursPeriod = serde_json::from_str(
+            r#"{ "open": { "day": 1, "time": "0930" }, "close": { "day": 1, "time": "1715" } }"#,
+        )
+        .unwrap();
+
+        assert_eq!(period.duration(), Some(Duration::minutes(465)));
+        assert!(!period.crosses_midnight());
+    }
+
+    #[test]
+    fn test_closing_at_2400() {
+        let period: PlaceOpeningHoursPeriod = serde_json::from_str(
+            r#"{
+                "open": { "day": 3, "time": "1800", "date": "2022-10-26" },
+                "close": { "day": 3, "time": "2400", "date": "2022-10-26" }
+            }"#,
+        )
+        .unwrap();
+
+        let close = period.close.as_ref().unwrap();
+        assert_eq!(close.day, Weekday::Thu);
+        assert_eq!(close.time, NaiveTime::MIN);
+        assert_eq!(close.date, NaiveDate::from_ymd_opt(2022, 10, 27));
+        assert!(!period.crosses_midnight());
+    }
+
+    #[test]
+    fn test_serialize_period_detail() {
+        let period: PlaceOpeningHoursPeriod = serde_json::from_str(
+            r#"{ "open": { "day": 2, "time": "0805" }, "close": { "day": 2, "time": "1900" } }"#,
+        )
+        .unwrap();
+
+        let json = serde_json::to_value(&period).unwrap();
+        assert_eq!(json["open"]["day"], 2);
+        assert_eq!(json["open"]["time"], "0805");
+        assert_eq!(json["close"]["time"], "1900");
+    }
+
+    #[test]
+    fn test_reject_invalid_period_detail() {
+        let invalid_day = serde_json::from_str::<PlaceOpeningHoursPeriod>(
+            r#"{ "open": { "day": 7, "time": "0900" } }"#,
+        );
+        assert!(invalid_day.is_err());
+
+        let invalid_time = serde_json::from_str::<PlaceOpeningHoursPeriod>(
+            r#"{ "open": { "day": 1, "time": "9:00" } }"#,
+        );
+        assert!(invalid_time.is_err());
+    }
+}
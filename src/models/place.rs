@@ -1,14 +1,23 @@
-use crate::models::LatLng;
+use crate::models::opening_hours::Schedule;
+use crate::models::{BusinessStatus, LatLng, OpeningIntervals, PlaceOpeningHours};
+use chrono::{DateTime, FixedOffset, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::ops::Range;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct AddressComponent {
+    /// The full text description or name of the address component as returned by the Geocoder.
     pub long_name: String,
+
+    /// An abbreviated textual name for the address component, if available.
     pub short_name: String,
-    pub types: String,
+
+    /// An array indicating the type of the address component.
+    pub types: Vec<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
 pub struct Bounds {
     pub northeast: LatLng,
     pub southwest: LatLng,
@@ -21,22 +30,12 @@ pub struct Geometry {
 }
 
 #[derive(Debug, Serialize, Deserialize)]
-pub struct PlaceOpeningHoursPeriodDetail {
-    pub day: u8,
-    pub time: String,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-pub struct PlaceOpeningHoursPeriod {
-    pub close: PlaceOpeningHoursPeriodDetail,
-    pub open: PlaceOpeningHoursPeriodDetail,
-}
+pub struct PlaceEditorialSummary {
+    /// The language of the previous fields. May not always be present.
+    pub language: Option<String>,
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct PlaceOpeningHours {
-    pub open_now: Option<bool>,
-    pub periods: Option<Vec<PlaceOpeningHoursPeriod>>,
-    pub weekday_text: Option<Vec<String>>,
+    /// A medium-length textual summary of the place.
+    pub overview: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -49,8 +48,11 @@ pub struct PlacePhoto {
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct PlusCode {
+    /// A 4 character area code and 6 character or longer local code (849VCWC8+R9).
     pub global_code: String,
-    pub compound_code: String,
+
+    /// A 6 character or longer local code with an explicit location (CWC8+R9, Mountain View, CA, USA).
+    pub compound_code: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -58,18 +60,25 @@ pub struct PlaceReview {
     pub author_name: String,
     pub rating: u8,
     pub relative_time_description: String,
-    pub time: u32,
+    pub time: u64,
     pub author_url: Option<String>,
     pub language: Option<String>,
+    pub original_language: Option<String>,
     pub profile_photo_url: Option<String>,
     pub text: Option<String>,
+    pub translated: Option<bool>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Place {
-    pub address_components: Option<AddressComponent>,
+    pub address_components: Option<Vec<AddressComponent>>,
     pub adr_address: Option<String>,
-    pub business_status: Option<String>,
+    pub business_status: Option<BusinessStatus>,
+    pub curbside_pickup: Option<bool>,
+    pub current_opening_hours: Option<PlaceOpeningHours>,
+    pub delivery: Option<bool>,
+    pub dine_in: Option<bool>,
+    pub editorial_summary: Option<PlaceEditorialSummary>,
     pub formatted_address: Option<String>,
     pub formatted_phone_number: Option<String>,
     pub geometry: Option<Geometry>,
@@ -79,16 +88,353 @@ pub struct Place {
     pub international_phone_number: Option<String>,
     pub name: Option<String>,
     pub opening_hours: Option<PlaceOpeningHours>,
+    pub permanently_closed: Option<bool>,
     pub photos: Option<Vec<PlacePhoto>>,
     pub place_id: Option<String>,
     pub plus_code: Option<PlusCode>,
     pub price_level: Option<u8>,
     pub rating: Option<f32>,
+    pub reference: Option<String>,
+    pub reservable: Option<bool>,
     pub reviews: Option<Vec<PlaceReview>>,
+    pub scope: Option<String>,
+    pub secondary_opening_hours: Option<Vec<PlaceOpeningHours>>,
+    pub serves_beer: Option<bool>,
+    pub serves_breakfast: Option<bool>,
+    pub serves_brunch: Option<bool>,
+    pub serves_dinner: Option<bool>,
+    pub serves_lunch: Option<bool>,
+    pub serves_vegetarian_food: Option<bool>,
+    pub serves_wine: Option<bool>,
+    pub takeout: Option<bool>,
     pub types: Option<Vec<String>>,
     pub url: Option<String>,
     pub user_ratings_total: Option<u32>,
     pub utc_offset: Option<i16>,
     pub vicinity: Option<String>,
     pub website: Option<String>,
+    pub wheelchair_accessible_entrance: Option<bool>,
+
+    /// Fields returned by Google that this crate does not model yet.
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// Offline opening-hours queries, evaluated in the place's own offset from `utc_offset`.
+/// Dates that current_opening_hours flags in `special_days` use its dated periods,
+/// any other date the regular weekly opening_hours.
+/// They return `None` when either `utc_offset` or the opening hours are missing.
+impl Place {
+    /// The offset from UTC the place currently observes.
+    pub fn local_offset(&self) -> Option<FixedOffset> {
+        FixedOffset::east_opt(self.utc_offset? as i32 * 60)
+    }
+
+    fn local(&self, instant: &DateTime<Utc>) -> Option<DateTime<FixedOffset>> {
+        Some(instant.with_timezone(&self.local_offset()?))
+    }
+
+    /// The effective opening intervals starting on the local calendar `date`.
+    pub fn hours_on(&self, date: NaiveDate) -> Option<OpeningIntervals> {
+        let offset = self.local_offset()?;
+
+        if let Some(current) = &self.current_opening_hours {
+            if current.has_exceptional_hours_on(date) {
+                return current.hours_on(date, &offset);
+            }
+        }
+
+        // nearby and text search results carry opening_hours with only open_now.
+        let has_periods =
+            |hours: &&PlaceOpeningHours| hours.periods.as_ref().is_some_and(|p| !p.is_empty());
+
+        self.opening_hours
+            .as_ref()
+            .filter(has_periods)
+            .or(self.current_opening_hours.as_ref())?
+            .hours_on(date, &offset)
+    }
+
+    fn schedule(&self) -> Schedule<impl Fn(NaiveDate) -> Option<OpeningIntervals> + '_> {
+        Schedule {
+            hours_on: move |date| self.hours_on(date),
+        }
+    }
+
+    pub fn is_open_at(&self, instant: &DateTime<Utc>) -> Option<bool> {
+        self.schedule().is_open_at(&self.local(instant)?)
+    }
+
+    pub fn next_open_after(&self, instant: &DateTime<Utc>) -> Option<DateTime<FixedOffset>> {
+        self.schedule().next_open_after(&self.local(instant)?)
+    }
+
+    pub fn next_close_after(&self, instant: &DateTime<Utc>) -> Option<DateTime<FixedOffset>> {
+        self.schedule().next_close_after(&self.local(instant)?)
+    }
+
+    pub fn open_intervals_in(&self, range: Range<DateTime<Utc>>) -> Option<OpeningIntervals> {
+        let range = self.local(&range.start)?..self.local(&range.end)?;
+
+        self.schedule().open_intervals_in(range)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Place;
+    use crate::models::BusinessStatus;
+    use chrono::{NaiveDate, TimeZone, Utc};
+
+    const DETAILS: &str = r##"{
+        "address_components": [
+            { "long_name": "48", "short_name": "48", "types": ["street_number"] },
+            { "long_name": "Pirrama Road", "short_name": "Pirrama Rd", "types": ["route"] },
+            { "long_name": "Pyrmont", "short_name": "Pyrmont", "types": ["locality", "political"] },
+            { "long_name": "Australia", "short_name": "AU", "types": ["country", "political"] }
+        ],
+        "adr_address": "<span class=\"street-address\">48 Pirrama Rd</span>, <span class=\"locality\">Pyrmont</span>",
+        "business_status": "OPERATIONAL",
+        "current_opening_hours": {
+            "open_now": false,
+            "periods": [
+                {
+                    "close": { "date": "2022-10-24", "day": 1, "time": "1700" },
+                    "open": { "date": "2022-10-24", "day": 1, "time": "0900" }
+                }
+            ],
+            "weekday_text": ["Monday: 9:00 AM – 5:00 PM"]
+        },
+        "delivery": false,
+        "dine_in": false,
+        "editorial_summary": {
+            "language": "en",
+            "overview": "Modern offices of the multinational tech company."
+        },
+        "formatted_address": "48 Pirrama Rd, Pyrmont NSW 2009, Australia",
+        "formatted_phone_number": "(02) 9374 4000",
+        "geometry": {
+            "location": { "lat": -33.866489, "lng": 151.1958561 },
+            "viewport": {
+                "northeast": { "lat": -33.8653881697085, "lng": 151.1971985302915 },
+                "southwest": { "lat": -33.86808613029149, "lng": 151.1945005697085 }
+            }
+        },
+        "icon": "https://maps.gstatic.com/mapfiles/place_api/icons/v1/png_71/generic_business-71.png",
+        "icon_background_color": "#7B9EB0",
+        "icon_mask_base_uri": "https://maps.gstatic.com/mapfiles/place_api/icons/v2/generic_pinlet",
+        "international_phone_number": "+61 2 9374 4000",
+        "name": "Google Workplace 6",
+        "opening_hours": {
+            "open_now": false,
+            "periods": [
+                {
+                    "close": { "day": 1, "time": "1700" },
+                    "open": { "day": 1, "time": "0900" }
+                }
+            ],
+            "weekday_text": ["Monday: 9:00 AM – 5:00 PM"]
+        },
+        "photos": [
+            {
+                "height": 3024,
+                "html_attributions": ["<a href=\"https://maps.google.com/maps/contrib/117600448889234589608\">Cynthia Wei</a>"],
+                "photo_reference": "Aap_uEC6jqtpflLS8GxQqPHBjlcwBf2sri0ZErU_Xc0XM1WUzclQgp2dA4dEw",
+                "width": 4032
+            }
+        ],
+        "place_id": "ChIJN1t_tDeuEmsRUsoyG83frY4",
+        "plus_code": {
+            "compound_code": "45MW+C8 Pyrmont NSW, Australia",
+            "global_code": "4RRH45MW+C8"
+        },
+        "rating": 4,
+        "reference": "ChIJN1t_tDeuEmsRUsoyG83frY4",
+        "reviews": [
+            {
+                "author_name": "Luke Archibald",
+                "author_url": "https://www.google.com/maps/contrib/113389359827989670652/reviews",
+                "language": "en",
+                "original_language": "en",
+                "profile_photo_url": "https://lh3.googleusercontent.com/a-/ALV-UjV3qKYt",
+                "rating": 1,
+                "relative_time_description": "a week ago",
+                "text": "Called regarding paid advertising google pages to the top of its site of a scam furniture website.",
+                "time": 1652286798,
+                "translated": false
+            }
+        ],
+        "secondary_opening_hours": [
+            {
+                "open_now": false,
+                "periods": [
+                    {
+                        "close": { "day": 5, "time": "2200" },
+                        "open": { "day": 5, "time": "1700" }
+                    }
+                ],
+                "type": "HAPPY_HOUR",
+                "weekday_text": ["Friday: 5:00 – 10:00 PM"]
+            }
+        ],
+        "serves_beer": true,
+        "serves_vegetarian_food": true,
+        "takeout": false,
+        "types": ["point_of_interest", "establishment"],
+        "url": "https://maps.google.com/?cid=10281119596374313554",
+        "user_ratings_total": 939,
+        "utc_offset": 600,
+        "vicinity": "48 Pirrama Road, Pyrmont",
+        "website": "http://google.com/",
+        "wheelchair_accessible_entrance": true
+    }"##;
+
+    #[test]
+    fn test_deserialize_place_details() {
+        let place: Place = serde_json::from_str(DETAILS).unwrap();
+
+        let components = place.address_components.unwrap();
+        assert_eq!(components.len(), 4);
+        assert_eq!(components[1].short_name, "Pirrama Rd");
+        assert_eq!(components[2].types, vec!["locality", "political"]);
+
+        let summary = place.editorial_summary.unwrap();
+        assert_eq!(summary.language.as_deref(), Some("en"));
+
+        let current = place.current_opening_hours.unwrap();
+        let periods = current.periods.unwrap();
+        assert_eq!(periods[0].open.date, NaiveDate::from_ymd_opt(2022, 10, 24));
+
+        let secondary = place.secondary_opening_hours.unwrap();
+        assert_eq!(secondary[0].hours_type.as_deref(), Some("HAPPY_HOUR"));
+
+        assert_eq!(place.business_status, Some(BusinessStatus::Operational));
+        assert_eq!(place.delivery, Some(false));
+        assert_eq!(place.serves_beer, Some(true));
+        assert_eq!(place.serves_wine, None);
+        assert_eq!(place.wheelchair_accessible_entrance, Some(true));
+        assert_eq!(place.reviews.unwrap()[0].translated, Some(false));
+        assert_eq!(place.utc_offset, Some(600));
+        assert!(place.extra.is_empty());
+    }
+
+    #[test]
+    fn test_place_is_open_at() {
+        let place: Place = serde_json::from_str(DETAILS).unwrap();
+
+        // Monday 2022-10-24 09:30 in Sydney (UTC+10).
+        let instant = Utc.with_ymd_and_hms(2022, 10, 23, 23, 30, 0).unwrap();
+        assert_eq!(place.is_open_at(&instant), Some(true));
+
+        let closing = place.next_close_after(&instant).unwrap();
+        assert_eq!(closing.to_rfc3339(), "2022-10-24T17:00:00+10:00");
+
+        let opening = place.next_open_after(&instant).unwrap();
+        assert_eq!(opening.to_rfc3339(), "2022-10-31T09:00:00+10:00");
+    }
+
+    const HOLIDAY: &str = r#"{
+        "utc_offset": 600,
+        "opening_hours": {
+            "periods": [
+                { "open": { "day": 1, "time": "0900" }, "close": { "day": 1, "time": "1700" } },
+                { "open": { "day": 2, "time": "0900" }, "close": { "day": 2, "time": "1700" } }
+            ]
+        },
+        "current_opening_hours": {
+            "periods": [
+                {
+                    "open": { "day": 2, "time": "1000", "date": "2022-12-27" },
+                    "close": { "day": 2, "time": "1400", "date": "2022-12-27" }
+                },
+                {
+                    "open": { "day": 1, "time": "0900", "date": "2023-01-02" },
+                    "close": { "day": 1, "time": "1700", "date": "2023-01-02" }
+                }
+            ],
+            "special_days": [
+                { "date": "2022-12-26", "exceptional_hours": true },
+                { "date": "2022-12-27", "exceptional_hours": true }
+            ]
+        }
+    }"#;
+
+    #[test]
+    fn test_place_hours_on_special_days() {
+        let place: Place = serde_json::from_str(HOLIDAY).unwrap();
+        let date = |day| NaiveDate::from_ymd_opt(2022, 12, day).unwrap();
+
+        // boxing day is closed, although regular hours would open on Mondays.
+        assert_eq!(place.hours_on(date(26)), Some(vec![]));
+
+        let shortened = place.hours_on(date(27)).unwrap();
+        assert_eq!(shortened.len(), 1);
+        assert_eq!(shortened[0].start.to_rfc3339(), "2022-12-27T10:00:00+10:00");
+        assert_eq!(shortened[0].end.to_rfc3339(), "2022-12-27T14:00:00+10:00");
+
+        // the following Monday is not special and uses the regular hours.
+        let regular = place
+            .hours_on(NaiveDate::from_ymd_opt(2023, 1, 2).unwrap())
+            .unwrap();
+        assert_eq!(regular[0].start.to_rfc3339(), "2023-01-02T09:00:00+10:00");
+    }
+
+    #[test]
+    fn test_place_queries_on_special_days() {
+        let place: Place = serde_json::from_str(HOLIDAY).unwrap();
+
+        // Monday 2022-12-26 10:00 in Sydney.
+        let boxing_day = Utc.with_ymd_and_hms(2022, 12, 26, 0, 0, 0).unwrap();
+        assert_eq!(place.is_open_at(&boxing_day), Some(false));
+        assert_eq!(
+            place.next_open_after(&boxing_day).unwrap().to_rfc3339(),
+            "2022-12-27T10:00:00+10:00"
+        );
+        assert_eq!(
+            place.next_close_after(&boxing_day).unwrap().to_rfc3339(),
+            "2022-12-27T14:00:00+10:00"
+        );
+    }
+
+    #[test]
+    fn test_place_hours_without_regular_periods() {
+        let place: Place = serde_json::from_str(
+            r#"{
+                "utc_offset": 600,
+                "opening_hours": { "open_now": true },
+                "current_opening_hours": {
+                    "periods": [{ "open": { "day": 1, "time": "0900" }, "close": { "day": 1, "time": "1700" } }]
+                }
+            }"#,
+        )
+        .unwrap();
+
+        let monday = place
+            .hours_on(NaiveDate::from_ymd_opt(2022, 10, 24).unwrap())
+            .unwrap();
+        assert_eq!(monday[0].start.to_rfc3339(), "2022-10-24T09:00:00+10:00");
+    }
+
+    #[test]
+    fn test_place_without_utc_offset() {
+        let place: Place = serde_json::from_str(
+            r#"{ "opening_hours": { "periods": [{ "open": { "day": 0, "time": "0000" } }] } }"#,
+        )
+        .unwrap();
+
+        assert_eq!(place.is_open_at(&Utc::now()), None);
+    }
+
+    #[test]
+    fn test_preserve_unknown_place_fields() {
+        let place: Place = serde_json::from_str(
+            r#"{ "place_id": "ChIJN1t_tDeuEmsRUsoyG83frY4", "good_for_children": true }"#,
+        )
+        .unwrap();
+
+        assert_eq!(place.extra["good_for_children"], true);
+
+        let json = serde_json::to_value(&place).unwrap();
+        assert_eq!(json["good_for_children"], true);
+    }
 }
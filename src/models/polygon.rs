@@ -0,0 +1,386 @@
+use crate::models::{Bounds, Error, LatLng};
+use serde_json::Value;
+use std::convert::TryFrom;
+
+/// A polygon with an exterior ring and optional holes, each ring a list of vertices.
+///
+/// Edges are straight lines in latitude/longitude, as in GeoJSON,
+/// which is accurate enough for areas the size of a city. Polygons crossing the antimeridian are not supported.
+/// Built through `TryFrom`, so that every ring has at least three vertices within range.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Polygon {
+    exterior: Vec<LatLng>,
+    holes: Vec<Vec<LatLng>>,
+}
+
+/// One or more polygons, such as a delivery zone made of several disjoint parts.
+/// Built through `TryFrom` or from a single `Polygon`, so that it is never empty.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MultiPolygon {
+    polygons: Vec<Polygon>,
+}
+
+/// Even-odd ray casting against a single ring.
+fn ring_contains(ring: &[LatLng], point: &LatLng) -> bool {
+    let mut inside = false;
+
+    for (i, a) in ring.iter().enumerate() {
+        let b = &ring[(i + 1) % ring.len()];
+
+        if (a.lat > point.lat) != (b.lat > point.lat) {
+            let lng = a.lng + (point.lat - a.lat) / (b.lat - a.lat) * (b.lng - a.lng);
+
+            if point.lng < lng {
+                inside = !inside;
+            }
+        }
+    }
+
+    inside
+}
+
+fn orientation(a: &LatLng, b: &LatLng, c: &LatLng) -> f64 {
+    (b.lng - a.lng) * (c.lat - a.lat) - (b.lat - a.lat) * (c.lng - a.lng)
+}
+
+fn segments_intersect(a: &LatLng, b: &LatLng, c: &LatLng, d: &LatLng) -> bool {
+    let (d1, d2) = (orientation(c, d, a), orientation(c, d, b));
+    let (d3, d4) = (orientation(a, b, c), orientation(a, b, d));
+
+    ((d1 > 0.0 && d2 < 0.0) || (d1 < 0.0 && d2 > 0.0))
+        && ((d3 > 0.0 && d4 < 0.0) || (d3 < 0.0 && d4 > 0.0))
+}
+
+fn edges(ring: &[LatLng]) -> impl Iterator<Item = (&LatLng, &LatLng)> {
+    ring.iter()
+        .enumerate()
+        .map(move |(i, a)| (a, &ring[(i + 1) % ring.len()]))
+}
+
+/// Checks a closed ring, its last vertex repeating the first one as in GeoJSON, and drops that repeated vertex.
+fn close_ring(mut ring: Vec<LatLng>) -> Result<Vec<LatLng>, Error> {
+    if let Some(vertex) = ring.iter().find(|vertex| {
+        !(-90.0..=90.0).contains(&vertex.lat) || !(-180.0..=180.0).contains(&vertex.lng)
+    }) {
+        return Err(format!("position {} is out of range", vertex).into());
+    }
+
+    if ring.len() < 2 || ring.first() != ring.last() {
+        return Err("ring must end with its first position".into());
+    }
+
+    ring.pop();
+
+    if ring.len() < 3 {
+        return Err("ring must have at least three distinct positions".into());
+    }
+
+    Ok(ring)
+}
+
+impl TryFrom<Vec<Vec<LatLng>>> for Polygon {
+    type Error = Error;
+
+    /// The exterior ring followed by the holes, each ring closed by repeating its first vertex.
+    fn try_from(rings: Vec<Vec<LatLng>>) -> Result<Self, Self::Error> {
+        let mut rings = rings
+            .into_iter()
+            .map(close_ring)
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter();
+
+        let exterior = rings.next().ok_or("polygon has no exterior ring")?;
+
+        Ok(Polygon {
+            exterior,
+            holes: rings.collect(),
+        })
+    }
+}
+
+impl Polygon {
+    /// The vertices of the exterior ring, without repeating the first one.
+    pub fn exterior(&self) -> &[LatLng] {
+        &self.exterior
+    }
+
+    pub fn holes(&self) -> &[Vec<LatLng>] {
+        &self.holes
+    }
+
+    pub fn contains(&self, point: &LatLng) -> bool {
+        ring_contains(&self.exterior, point)
+            && !self.holes.iter().any(|hole| ring_contains(hole, point))
+    }
+
+    pub fn bounds(&self) -> Bounds {
+        let mut bounds = Bounds::new(self.exterior[0], self.exterior[0]);
+
+        for vertex in &self.exterior {
+            bounds.southwest.lat = bounds.southwest.lat.min(vertex.lat);
+            bounds.southwest.lng = bounds.southwest.lng.min(vertex.lng);
+            bounds.northeast.lat = bounds.northeast.lat.max(vertex.lat);
+            bounds.northeast.lng = bounds.northeast.lng.max(vertex.lng);
+        }
+
+        bounds
+    }
+
+    /// Whether the polygon and the bounds share any point.
+    pub fn intersects(&self, bounds: &Bounds) -> bool {
+        let corners = [
+            bounds.southwest,
+            LatLng::new(bounds.southwest.lat, bounds.northeast.lng),
+            bounds.northeast,
+            LatLng::new(bounds.northeast.lat, bounds.southwest.lng),
+        ];
+
+        if corners.iter().any(|corner| self.contains(corner)) {
+            return true;
+        }
+
+        if self.exterior.iter().any(|vertex| bounds.contains(vertex)) {
+            return true;
+        }
+
+        edges(&self.exterior)
+            .any(|(a, b)| edges(&corners).any(|(c, d)| segments_intersect(a, b, c, d)))
+    }
+
+    fn from_coordinates(coordinates: &Value) -> Result<Polygon, Error> {
+        let rings = coordinates
+            .as_array()
+            .ok_or("polygon coordinates must be an array of rings")?
+            .iter()
+            .map(ring_from_coordinates)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Polygon::try_from(rings)
+    }
+}
+
+/// Reads a GeoJSON linear ring of `[longitude, latitude]` positions.
+fn ring_from_coordinates(coordinates: &Value) -> Result<Vec<LatLng>, Error> {
+    Ok(coordinates
+        .as_array()
+        .ok_or("ring must be an array of positions")?
+        .iter()
+        .map(|position| match position.as_array().map(|p| p.as_slice()) {
+            Some([lng, lat, ..]) => match (lat.as_f64(), lng.as_f64()) {
+                (Some(lat), Some(lng)) => Ok(LatLng::new(lat, lng)),
+                _ => Err(format!("invalid position {}", position)),
+            },
+            _ => Err(format!("invalid position {}", position)),
+        })
+        .collect::<Result<Vec<_>, _>>()?)
+}
+
+impl TryFrom<Vec<Polygon>> for MultiPolygon {
+    type Error = Error;
+
+    fn try_from(polygons: Vec<Polygon>) -> Result<Self, Self::Error> {
+        if polygons.is_empty() {
+            return Err("geojson has no polygon".into());
+        }
+
+        Ok(MultiPolygon { polygons })
+    }
+}
+
+impl MultiPolygon {
+    /// Reads a GeoJSON Polygon or MultiPolygon geometry, or a Feature or FeatureCollection of them.
+    pub fn from_geojson(geojson: &str) -> Result<MultiPolygon, Error> {
+        let value: Value = serde_json::from_str(geojson)?;
+
+        MultiPolygon::from_geojson_value(&value)
+    }
+
+    pub fn from_geojson_value(value: &Value) -> Result<MultiPolygon, Error> {
+        let polygons = match value["type"].as_str() {
+            Some("Polygon") => vec![Polygon::from_coordinates(&value["coordinates"])?],
+            Some("MultiPolygon") => value["coordinates"]
+                .as_array()
+                .ok_or("multipolygon coordinates must be an array of polygons")?
+                .iter()
+                .map(Polygon::from_coordinates)
+                .collect::<Result<_, _>>()?,
+            Some("Feature") => MultiPolygon::from_geojson_value(&value["geometry"])?.polygons,
+            Some("FeatureCollection") => value["features"]
+                .as_array()
+                .ok_or("feature collection must have features")?
+                .iter()
+                .map(MultiPolygon::from_geojson_value)
+                .collect::<Result<Vec<_>, _>>()?
+                .into_iter()
+                .flat_map(|multi| multi.polygons)
+                .collect(),
+            other => {
+                return Err(format!(
+                    "expected a Polygon or MultiPolygon, found {}",
+                    other.unwrap_or("no type")
+                )
+                .into())
+            }
+        };
+
+        MultiPolygon::try_from(polygons)
+    }
+
+    pub fn polygons(&self) -> &[Polygon] {
+        &self.polygons
+    }
+
+    pub fn contains(&self, point: &LatLng) -> bool {
+        self.polygons.iter().any(|polygon| polygon.contains(point))
+    }
+
+    pub fn bounds(&self) -> Bounds {
+        self.polygons
+            .iter()
+            .map(Polygon::bounds)
+            .reduce(|a, b| {
+                Bounds::new(
+                    LatLng::new(
+                        a.southwest.lat.min(b.southwest.lat),
+                        a.southwest.lng.min(b.southwest.lng),
+                    ),
+                    LatLng::new(
+                        a.northeast.lat.max(b.northeast.lat),
+                        a.northeast.lng.max(b.northeast.lng),
+                    ),
+                )
+            })
+            .expect("a multipolygon has at least one polygon")
+    }
+
+    pub fn intersects(&self, bounds: &Bounds) -> bool {
+        self.polygons
+            .iter()
+            .any(|polygon| polygon.intersects(bounds))
+    }
+}
+
+impl From<Polygon> for MultiPolygon {
+    fn from(polygon: Polygon) -> Self {
+        MultiPolygon {
+            polygons: vec![polygon],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MultiPolygon, Polygon};
+    use crate::models::{Bounds, LatLng};
+    use std::convert::TryFrom;
+
+    const SQUARE_WITH_HOLE: &str = r#"{
+        "type": "Polygon",
+        "coordinates": [
+            [[0.0, 0.0], [10.0, 0.0], [10.0, 10.0], [0.0, 10.0], [0.0, 0.0]],
+            [[4.0, 4.0], [6.0, 4.0], [6.0, 6.0], [4.0, 6.0], [4.0, 4.0]]
+        ]
+    }"#;
+
+    #[test]
+    fn test_polygon_contains() {
+        let area = MultiPolygon::from_geojson(SQUARE_WITH_HOLE).unwrap();
+
+        assert_eq!(area.polygons()[0].exterior().len(), 4);
+        assert_eq!(area.polygons()[0].holes().len(), 1);
+        assert!(area.contains(&LatLng::new(1.0, 1.0)));
+        assert!(area.contains(&LatLng::new(9.0, 5.0)));
+        assert!(!area.contains(&LatLng::new(5.0, 5.0)));
+        assert!(!area.contains(&LatLng::new(11.0, 5.0)));
+        assert!(!area.contains(&LatLng::new(-0.1, 5.0)));
+    }
+
+    #[test]
+    fn test_multipolygon_feature_collection() {
+        let area = MultiPolygon::from_geojson(
+            r#"{
+                "type": "FeatureCollection",
+                "features": [{
+                    "type": "Feature",
+                    "properties": { "name": "zones" },
+                    "geometry": {
+                        "type": "MultiPolygon",
+                        "coordinates": [
+                            [[[0, 0], [1, 0], [0, 1], [0, 0]]],
+                            [[[5, 5], [6, 5], [6, 6], [5, 6], [5, 5]]]
+                        ]
+                    }
+                }]
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(area.polygons().len(), 2);
+        assert!(area.contains(&LatLng::new(0.2, 0.2)));
+        assert!(area.contains(&LatLng::new(5.5, 5.5)));
+        assert!(!area.contains(&LatLng::new(0.8, 0.8)));
+        assert!(!area.contains(&LatLng::new(3.0, 3.0)));
+        assert_eq!(
+            area.bounds(),
+            Bounds::new(LatLng::new(0.0, 0.0), LatLng::new(6.0, 6.0))
+        );
+    }
+
+    #[test]
+    fn test_polygon_intersects_bounds() {
+        let area = MultiPolygon::from_geojson(SQUARE_WITH_HOLE).unwrap();
+        let cell = |lat: f64, lng: f64, size: f64| {
+            Bounds::new(LatLng::new(lat, lng), LatLng::new(lat + size, lng + size))
+        };
+
+        assert!(area.intersects(&cell(1.0, 1.0, 1.0)));
+        assert!(area.intersects(&cell(-1.0, -1.0, 2.0)));
+        assert!(area.intersects(&cell(-1.0, -1.0, 12.0)));
+        assert!(!area.intersects(&cell(4.5, 4.5, 1.0)));
+        assert!(!area.intersects(&cell(11.0, 11.0, 1.0)));
+
+        // the edges cross without any vertex of one inside the other.
+        let thin = Bounds::new(LatLng::new(-1.0, 2.0), LatLng::new(11.0, 3.0));
+        let cross = MultiPolygon::from_geojson(
+            r#"{ "type": "Polygon", "coordinates": [[[0, 4], [5, 4], [5, 5], [0, 5], [0, 4]]] }"#,
+        )
+        .unwrap();
+        assert!(cross.intersects(&thin));
+    }
+
+    #[test]
+    fn test_reject_invalid_geojson() {
+        assert!(
+            MultiPolygon::from_geojson(r#"{ "type": "Point", "coordinates": [0, 0] }"#).is_err()
+        );
+        assert!(MultiPolygon::from_geojson(
+            r#"{ "type": "Polygon", "coordinates": [[[0, 0], [1, 1], [0, 0]]] }"#
+        )
+        .is_err());
+
+        for coordinates in [
+            "[]",
+            "[[]]",
+            // not closed.
+            "[[[0, 0], [1, 0], [1, 1], [0, 1]]]",
+            // a hole that is not closed.
+            "[[[0, 0], [4, 0], [4, 4], [0, 0]], [[1, 1], [2, 1], [2, 2]]]",
+            "[[[0, 0], [1, 0], [1, 91], [0, 0]]]",
+            "[[[0, 0], [181, 0], [1, 1], [0, 0]]]",
+        ] {
+            let geojson = format!(r#"{{ "type": "Polygon", "coordinates": {} }}"#, coordinates);
+            assert!(
+                MultiPolygon::from_geojson(&geojson).is_err(),
+                "{}",
+                coordinates
+            );
+        }
+
+        assert!(
+            MultiPolygon::from_geojson(r#"{ "type": "MultiPolygon", "coordinates": [] }"#).is_err()
+        );
+        assert!(MultiPolygon::try_from(vec![]).is_err());
+        assert!(Polygon::try_from(vec![]).is_err());
+        assert!(Polygon::try_from(vec![vec![LatLng::new(f64::NAN, 0.0); 4]]).is_err());
+    }
+}
@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum SearchStatus {
     /// indicating the API request was successful.
@@ -30,4 +30,7 @@ pub enum SearchStatus {
 
     /// indicating an unknown error.
     UnknownError,
+
+    /// indicating that the referenced location, such as a place_id, was not found in the Places database.
+    NotFound,
 }
@@ -1,5 +1,8 @@
+mod pages;
 mod request;
 mod response;
 
+pub(crate) use pages::fetch_page;
+pub use pages::Page;
 pub use request::*;
 pub use response::*;
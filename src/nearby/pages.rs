@@ -0,0 +1,219 @@
+use super::{Distance, Prominence, Response};
+use crate::models::{Error, SearchStatus};
+use crate::{fetch, SearchParams, SendUrl};
+use std::future::Future;
+use std::time::Duration;
+
+/// Google needs a short while before a next_page_token becomes valid,
+/// and answers INVALID_REQUEST until then.
+const PAGE_TOKEN_DELAY: Duration = Duration::from_secs(2);
+const PAGE_TOKEN_RETRIES: usize = 5;
+
+/// Which page of a search to fetch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Page {
+    First,
+
+    /// The page a next_page_token just returned points to, retried until the token becomes valid.
+    Next(String),
+
+    /// The page a token saved a while ago points to, sent once since the token is either valid by now or expired.
+    Saved(String),
+}
+
+async fn send_params(url: &'static str, params: Vec<(String, String)>) -> Result<Response, Error> {
+    fetch(url, &params).await
+}
+
+/// Fetches the given page of results, waiting for a next page token to become valid.
+pub(crate) async fn fetch_page<T>(request: &T, page: Page) -> Result<Response, Error>
+where
+    T: SearchParams + SendUrl,
+{
+    fetch_page_with(request, page, &send_params).await
+}
+
+/// Like `fetch_page`, sending every request, retries included, through `send`.
+async fn fetch_page_with<T, F, Fut>(request: &T, page: Page, send: &F) -> Result<Response, Error>
+where
+    T: SearchParams + SendUrl,
+    F: Fn(&'static str, Vec<(String, String)>) -> Fut,
+    Fut: Future<Output = Result<Response, Error>>,
+{
+    let params = request.get_params();
+
+    let (token, patient) = match page {
+        Page::First => return send(request.get_url(), params).await,
+        Page::Next(token) => (token, true),
+        Page::Saved(token) => (token, false),
+    };
+
+    let mut page_params = params
+        .into_iter()
+        .filter(|(key, _)| key != "pagetoken")
+        .collect::<Vec<_>>();
+
+    page_params.push(("pagetoken".to_owned(), token));
+
+    let attempts = if patient { PAGE_TOKEN_RETRIES } else { 1 };
+
+    for _ in 0..attempts {
+        if patient {
+            tokio::time::sleep(PAGE_TOKEN_DELAY).await;
+        }
+
+        let next = send(request.get_url(), page_params.clone()).await?;
+
+        match next.status {
+            SearchStatus::InvalidRequest if patient => continue,
+            SearchStatus::Ok | SearchStatus::ZeroResults => return Ok(next),
+            status => {
+                return Err(format!(
+                    "fetching the next page failed with {:?}: {}",
+                    status,
+                    next.error_message.unwrap_or_default()
+                )
+                .into())
+            }
+        }
+    }
+
+    Err("next_page_token never became valid".into())
+}
+
+/// Follows next_page_token until the last page and returns every result in a single response,
+/// sending every request through `send`, which takes the URL and the parameters.
+async fn send_all_pages_with<T, F, Fut>(request: &T, send: F) -> Result<Response, Error>
+where
+    T: SearchParams + SendUrl,
+    F: Fn(&'static str, Vec<(String, String)>) -> Fut,
+    Fut: Future<Output = Result<Response, Error>>,
+{
+    let mut response = fetch_page_with(request, Page::First, &send).await?;
+
+    while let (SearchStatus::Ok, Some(token)) = (response.status, response.next_page_token.take()) {
+        let page = fetch_page_with(request, Page::Next(token), &send).await?;
+
+        response.html_attributions.extend(page.html_attributions);
+        response.results.extend(page.results);
+        response.next_page_token = page.next_page_token;
+    }
+
+    Ok(response)
+}
+
+impl Prominence {
+    pub async fn send_all_pages(&self) -> Result<Response, Error> {
+        send_all_pages_with(self, send_params).await
+    }
+
+    /// Follows every page like `send_all_pages`, with a custom `send` such as one waiting for a rate limiter.
+    pub async fn send_all_pages_with<F, Fut>(&self, send: F) -> Result<Response, Error>
+    where
+        F: Fn(&'static str, Vec<(String, String)>) -> Fut,
+        Fut: Future<Output = Result<Response, Error>>,
+    {
+        send_all_pages_with(self, send).await
+    }
+}
+
+impl Distance {
+    pub async fn send_all_pages(&self) -> Result<Response, Error> {
+        send_all_pages_with(self, send_params).await
+    }
+
+    /// Follows every page like `send_all_pages`, with a custom `send` such as one waiting for a rate limiter.
+    pub async fn send_all_pages_with<F, Fut>(&self, send: F) -> Result<Response, Error>
+    where
+        F: Fn(&'static str, Vec<(String, String)>) -> Fut,
+        Fut: Future<Output = Result<Response, Error>>,
+    {
+        send_all_pages_with(self, send).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{fetch_page_with, Page, Response};
+    use crate::models::Error;
+    use crate::Client;
+    use std::cell::{Cell, RefCell};
+    use std::time::Duration;
+
+    #[tokio::test(start_paused = true)]
+    async fn test_send_all_pages_with() {
+        let sent = RefCell::new(vec![]);
+
+        let send = |_url: &'static str, params: Vec<(String, String)>| {
+            let token = params
+                .iter()
+                .find(|(key, _)| key == "pagetoken")
+                .map(|(_, token)| token.clone());
+            sent.borrow_mut().push(token.clone());
+            let attempt = sent.borrow().len();
+
+            async move {
+                let (results, next, status) = match token.as_deref() {
+                    None => (vec!["a", "b"], Some("second"), "OK"),
+                    // the token is not valid yet the first time it is sent.
+                    Some("second") if attempt == 2 => (vec![], None, "INVALID_REQUEST"),
+                    Some("second") => (vec!["c"], None, "OK"),
+                    Some(token) => panic!("unexpected token {}", token),
+                };
+
+                let results = results
+                    .into_iter()
+                    .map(|place_id| serde_json::json!({ "place_id": place_id }))
+                    .collect::<Vec<_>>();
+
+                Ok::<Response, Error>(serde_json::from_value(serde_json::json!({
+                    "html_attributions": [],
+                    "results": results,
+                    "status": status,
+                    "next_page_token": next
+                }))?)
+            }
+        };
+
+        let response = Client::new("hello kirby")
+            .nearby(-33.8587, 151.2140)
+            .prominence(500)
+            .send_all_pages_with(send)
+            .await
+            .unwrap();
+
+        assert_eq!(response.results.len(), 3);
+        assert_eq!(
+            *sent.borrow(),
+            vec![None, Some("second".to_owned()), Some("second".to_owned())]
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_saved_page_token_is_sent_once() {
+        let sent = Cell::new(0);
+
+        let send = |_url: &'static str, _params: Vec<(String, String)>| {
+            sent.set(sent.get() + 1);
+
+            async {
+                Ok::<Response, Error>(serde_json::from_value(serde_json::json!({
+                    "html_attributions": [],
+                    "results": [],
+                    "status": "INVALID_REQUEST"
+                }))?)
+            }
+        };
+
+        let request = Client::new("hello kirby")
+            .nearby(-33.8587, 151.2140)
+            .prominence(500);
+        let start = tokio::time::Instant::now();
+
+        let response = fetch_page_with(&request, Page::Saved("expired".to_owned()), &send).await;
+
+        assert!(response.is_err());
+        assert_eq!(sent.get(), 1);
+        assert_eq!(start.elapsed(), Duration::ZERO);
+    }
+}
@@ -2,7 +2,7 @@ use std::fmt::Display;
 
 use crate::{models::LatLng, SearchParams, SendUrl};
 
-#[derive(Debug, Default)]
+#[derive(Debug, Clone, Default)]
 pub struct Request {
     pub url: &'static str,
 
@@ -130,7 +130,7 @@ pub struct Prominence {
     ///     - without keyword or name
     ///         - rankby=prominence (default): 50,000 meters
     ///         - rankby=distance: A few kilometers depending on density of area.
-    ///                             radius will not be accepted, and will result in an INVALID_REQUEST.
+    ///           radius will not be accepted, and will result in an INVALID_REQUEST.
     /// - Query Autocomplete: 50,000 meters
     /// - Text Search: 50,000 meters
     pub radius: u32,
@@ -138,7 +138,7 @@ pub struct Prominence {
 
 impl SendUrl for Prominence {
     fn get_url(&self) -> &'static str {
-        return self.request.url;
+        self.request.url
     }
 }
 
@@ -193,7 +193,7 @@ impl Distance {
 
 impl SendUrl for Distance {
     fn get_url(&self) -> &'static str {
-        return self.request.url;
+        self.request.url
     }
 }
 
@@ -1,5 +1,6 @@
 use crate::models::*;
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Response {
@@ -9,4 +10,155 @@ pub struct Response {
     pub error_message: Option<String>,
     pub info_messages: Option<Vec<String>>,
     pub next_page_token: Option<String>,
+
+    /// Fields returned by Google that this crate does not model yet.
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// Distances are measured from `origin`, usually the location the search was made around.
+impl Response {
+    /// The results that have a location, in Google's order, with their distance and bearing.
+    pub fn annotate(&self, origin: &LatLng) -> Vec<AnnotatedPlace<'_>> {
+        annotate_places(&self.results, origin)
+    }
+
+    /// Reorders the results from the nearest to the farthest.
+    pub fn sort_by_distance(&mut self, origin: &LatLng) {
+        sort_places_by_distance(&mut self.results, origin);
+    }
+
+    /// Drops the results farther than `max_distance` meters.
+    pub fn retain_within(&mut self, origin: &LatLng, max_distance: f64) {
+        retain_places_within(&mut self.results, origin, max_distance);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Response;
+    use crate::models::{BusinessStatus, LatLng, SearchStatus};
+
+    const NEARBY: &str = r##"{
+        "html_attributions": [],
+        "next_page_token": "Aap_uEA7vb0DDYVJWEaX3O-AtYp77AaswQKSGtDaimt3gt7QCNpdjp1BkdM6acJ96xTec3tsV_ZJNL_JP-lqsVxydG3nh739RE_hepOOL05tfJh2_ranjMadb3VoBYFvF0ma6S24qZ6QJUuV6sSRrhCskSBP5C1myCzsebztMfGvm7ij3gZT",
+        "results": [
+            {
+                "business_status": "OPERATIONAL",
+                "geometry": {
+                    "location": { "lat": -33.8587323, "lng": 151.2100055 },
+                    "viewport": {
+                        "northeast": { "lat": -33.85739847010727, "lng": 151.2112436298927 },
+                        "southwest": { "lat": -33.86009812989271, "lng": 151.2085439701072 }
+                    }
+                },
+                "icon": "https://maps.gstatic.com/mapfiles/place_api/icons/v1/png_71/bar-71.png",
+                "icon_background_color": "#FF9E67",
+                "icon_mask_base_uri": "https://maps.gstatic.com/mapfiles/place_api/icons/v2/bar_pinlet",
+                "name": "Cruise Bar",
+                "opening_hours": { "open_now": false },
+                "photos": [
+                    {
+                        "height": 608,
+                        "html_attributions": ["<a href=\"https://maps.google.com/maps/contrib/112582655193348962755\">A Google User</a>"],
+                        "photo_reference": "Aap_uECvJIZuXT-uLDYm4DPbrV7gXVPeplbTWUgcOJ6rnfc4bUYCEAwPU_AmXGIaj0PDhWPbmrjQC8hhuXRJQjnA1-iREGEn7I0ZneHg5OP1mDT7lYVpa1hUPoz7cn8iCGBN9MynjOPSUe-UooRrFw2XEXOLgRJ-uKr6tGQUp77CWVocpcoG",
+                        "width": 1080
+                    }
+                ],
+                "place_id": "ChIJi6C1MxquEmsR9-c-3O48ykI",
+                "plus_code": {
+                    "compound_code": "46R6+G2 The Rocks, New South Wales",
+                    "global_code": "4RRH46R6+G2"
+                },
+                "price_level": 2,
+                "rating": 4,
+                "reference": "ChIJi6C1MxquEmsR9-c-3O48ykI",
+                "scope": "GOOGLE",
+                "types": ["bar", "restaurant", "food", "point_of_interest", "establishment"],
+                "user_ratings_total": 1269,
+                "vicinity": "Level 1, 2 and 3, Overseas Passenger Terminal, Circular Quay W, The Rocks"
+            },
+            {
+                "business_status": "CLOSED_TEMPORARILY",
+                "geometry": {
+                    "location": { "lat": -33.8605523, "lng": 151.2092582 },
+                    "viewport": {
+                        "northeast": { "lat": -33.85922001970849, "lng": 151.2106116302915 },
+                        "southwest": { "lat": -33.86191798029149, "lng": 151.2079136697085 }
+                    }
+                },
+                "icon": "https://maps.gstatic.com/mapfiles/place_api/icons/v1/png_71/restaurant-71.png",
+                "name": "Sydney Harbour Dinner Cruises",
+                "permanently_closed": true,
+                "place_id": "ChIJM1mOVTS6EmsRKaDzrTsgids",
+                "types": ["restaurant", "food", "point_of_interest", "establishment"],
+                "vicinity": "32 The Promenade, King Street Wharf 5, Sydney"
+            }
+        ],
+        "status": "OK"
+    }"##;
+
+    #[test]
+    fn test_deserialize_nearby_response() {
+        let response: Response = serde_json::from_str(NEARBY).unwrap();
+
+        assert!(matches!(response.status, SearchStatus::Ok));
+        assert!(response.next_page_token.is_some());
+        assert_eq!(response.results.len(), 2);
+
+        let first = &response.results[0];
+        assert_eq!(first.name.as_deref(), Some("Cruise Bar"));
+        assert_eq!(first.price_level, Some(2));
+        assert_eq!(first.scope.as_deref(), Some("GOOGLE"));
+
+        let second = &response.results[1];
+        assert_eq!(
+            second.business_status,
+            Some(BusinessStatus::ClosedTemporarily)
+        );
+        assert_eq!(second.permanently_closed, Some(true));
+        assert!(second.plus_code.is_none());
+    }
+
+    #[test]
+    fn test_deserialize_nearby_zero_results() {
+        let response: Response = serde_json::from_str(
+            r#"{ "html_attributions": [], "results": [], "status": "ZERO_RESULTS" }"#,
+        )
+        .unwrap();
+
+        assert!(matches!(response.status, SearchStatus::ZeroResults));
+        assert!(response.results.is_empty());
+    }
+
+    #[test]
+    fn test_annotate_nearby_results() {
+        let response: Response = serde_json::from_str(NEARBY).unwrap();
+
+        // Sydney Opera House, north-east of both results.
+        let origin = LatLng::new(-33.8567844, 151.2152967);
+        let annotated = response.annotate(&origin);
+
+        assert_eq!(annotated.len(), 2);
+        assert_eq!(annotated[0].place.name.as_deref(), Some("Cruise Bar"));
+        assert!((annotated[0].distance - 534.4).abs() < 1.0);
+        assert!((annotated[0].bearing - 246.1).abs() < 0.1);
+    }
+
+    #[test]
+    fn test_sort_and_filter_nearby_results() {
+        let mut response: Response = serde_json::from_str(NEARBY).unwrap();
+
+        // Barangaroo, closer to the second result.
+        let origin = LatLng::new(-33.8615, 151.2017);
+
+        response.sort_by_distance(&origin);
+        assert_eq!(
+            response.results[0].name.as_deref(),
+            Some("Sydney Harbour Dinner Cruises")
+        );
+
+        response.retain_within(&origin, 800.0);
+        assert_eq!(response.results.len(), 1);
+    }
 }
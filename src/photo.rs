@@ -0,0 +1,104 @@
+//! Place Photos, which answers with the image itself rather than JSON.
+
+use crate::fetch::redact_key;
+use crate::models::Error;
+use crate::SearchParams;
+use reqwest::header::CONTENT_TYPE;
+use reqwest::Url;
+
+/// The largest width or height Google serves, in pixels.
+pub const MAX_SIZE: u32 = 1600;
+
+/// Sent with `download` only. It has no `SendUrl` implementation, which keeps it out of the `Send` and
+/// `SendRaw` traits that parse the answer as JSON.
+#[derive(Debug, Default)]
+pub struct Request {
+    pub url: &'static str,
+    pub token: String,
+
+    /// The photo_reference of a photo from a search or details response.
+    pub photo_reference: String,
+
+    /// The image is scaled down to fit, keeping its aspect ratio, between 1 and 1600.
+    /// At least one of the two bounds is required.
+    pub max_width: Option<u32>,
+    pub max_height: Option<u32>,
+}
+
+/// An image and its media type, such as "image/jpeg".
+#[derive(Debug, Clone)]
+pub struct Photo {
+    pub content_type: Option<String>,
+    pub bytes: Vec<u8>,
+}
+
+impl Photo {
+    /// The usual file extension of the image, "jpg" when the media type is unknown.
+    pub fn extension(&self) -> &'static str {
+        match self.content_type.as_deref() {
+            Some("image/png") => "png",
+            Some("image/gif") => "gif",
+            Some("image/webp") => "webp",
+            _ => "jpg",
+        }
+    }
+}
+
+impl Request {
+    pub fn set_max_width(&mut self, max_width: u32) -> &mut Self {
+        self.max_width = Some(max_width);
+
+        self
+    }
+
+    pub fn set_max_height(&mut self, max_height: u32) -> &mut Self {
+        self.max_height = Some(max_height);
+
+        self
+    }
+
+    /// Follows the redirect to the image and downloads it.
+    pub async fn download(&self) -> Result<Photo, Error> {
+        if self.max_width.is_none() && self.max_height.is_none() {
+            return Err("a photo request needs a max width or a max height".into());
+        }
+
+        let url = Url::parse_with_params(self.url, self.get_params())?;
+
+        let res = reqwest::get(url)
+            .await
+            .and_then(reqwest::Response::error_for_status)
+            .map_err(redact_key)?;
+
+        let content_type = res
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_owned);
+
+        Ok(Photo {
+            content_type,
+            bytes: res.bytes().await.map_err(redact_key)?.to_vec(),
+        })
+    }
+}
+
+impl SearchParams for Request {
+    fn get_params(&self) -> Vec<(String, String)> {
+        let mut params = vec![];
+
+        params.push(("key".to_owned(), self.token.to_owned()));
+
+        params.push(("photo_reference".to_owned(), self.photo_reference.clone()));
+
+        if let Some(max_width) = self.max_width {
+            params.push(("maxwidth".to_owned(), max_width.to_string()))
+        }
+
+        if let Some(max_height) = self.max_height {
+            params.push(("maxheight".to_owned(), max_height.to_string()))
+        }
+
+        params
+    }
+}
@@ -0,0 +1,322 @@
+use super::Response;
+use crate::models::{LatLng, Place};
+use crate::resolve::{address_of, phone_of, phones_match, text_similarity, PairScore, Resolver};
+
+/// What is known about the place a Find Place search looks for, such as a row from a CRM.
+#[derive(Debug, Clone, Default)]
+pub struct MatchQuery {
+    pub name: Option<String>,
+    pub address: Option<String>,
+    pub phone: Option<String>,
+    pub location: Option<LatLng>,
+}
+
+impl MatchQuery {
+    pub fn new(name: impl Into<String>) -> MatchQuery {
+        MatchQuery {
+            name: Some(name.into()),
+            ..Default::default()
+        }
+    }
+
+    pub fn set_address(mut self, address: impl Into<String>) -> MatchQuery {
+        self.address = Some(address.into());
+
+        self
+    }
+
+    pub fn set_phone(mut self, phone: impl Into<String>) -> MatchQuery {
+        self.phone = Some(phone.into());
+
+        self
+    }
+
+    pub fn set_location(mut self, location: LatLng) -> MatchQuery {
+        self.location = Some(location);
+
+        self
+    }
+}
+
+/// A candidate and how well it matches the query.
+#[derive(Debug, Clone, Copy)]
+pub struct ScoredCandidate<'a> {
+    pub place: &'a Place,
+    pub score: PairScore,
+}
+
+#[derive(Debug)]
+pub enum BestMatch<'a> {
+    /// The best candidate reaches the threshold and leads every other one by at least the margin.
+    Unique(ScoredCandidate<'a>),
+
+    /// The best candidate reaches the threshold, but others score within the margin of it, best first.
+    /// The others may fall short of the threshold.
+    Ambiguous(Vec<ScoredCandidate<'a>>),
+
+    /// No candidate reaches the threshold.
+    NotFound,
+}
+
+/// Scores Find Place candidates against a query.
+#[derive(Debug, Clone)]
+pub struct Matcher {
+    /// Weights of the signals, and the distance in meters from which a location scores nothing.
+    pub resolver: Resolver,
+
+    /// How far ahead of the runner-up the best candidate must score to be picked.
+    pub margin: f64,
+}
+
+impl Default for Matcher {
+    fn default() -> Self {
+        Matcher {
+            resolver: Resolver::new().set_max_distance(500.0),
+            margin: 0.1,
+        }
+    }
+}
+
+impl Matcher {
+    pub fn new() -> Matcher {
+        Matcher::default()
+    }
+
+    pub fn set_resolver(mut self, resolver: Resolver) -> Matcher {
+        self.resolver = resolver;
+
+        self
+    }
+
+    pub fn set_margin(mut self, margin: f64) -> Matcher {
+        self.margin = margin;
+
+        self
+    }
+
+    /// Scores `place` on the signals of the query, a signal the query has but the place lacks scoring nothing.
+    pub fn score(&self, query: &MatchQuery, place: &Place) -> PairScore {
+        let name = query
+            .name
+            .as_deref()
+            .zip(place.name.as_deref())
+            .map(|(a, b)| text_similarity(a, b));
+        let distance = query
+            .location
+            .as_ref()
+            .zip(place.location())
+            .map(|(a, b)| a.haversine_distance(b));
+        let phone = query
+            .phone
+            .as_deref()
+            .zip(phone_of(place))
+            .and_then(|(a, b)| phones_match(a, b));
+        let address = query
+            .address
+            .as_deref()
+            .zip(address_of(place))
+            .map(|(a, b)| text_similarity(a, b));
+
+        let expected = [
+            query.name.is_some(),
+            query.location.is_some(),
+            query.phone.is_some(),
+            query.address.is_some(),
+        ];
+
+        self.resolver
+            .combine(name, distance, phone, address, expected)
+    }
+
+    /// Every candidate with its score, best first.
+    pub fn rank<'a>(
+        &self,
+        query: &MatchQuery,
+        candidates: &'a [Place],
+    ) -> Vec<ScoredCandidate<'a>> {
+        let mut scored = candidates
+            .iter()
+            .map(|place| ScoredCandidate {
+                place,
+                score: self.score(query, place),
+            })
+            .collect::<Vec<_>>();
+
+        scored.sort_by(|a, b| b.score.total.total_cmp(&a.score.total));
+
+        scored
+    }
+
+    /// The candidate scoring at least `threshold`, unless another one scores within the margin of it,
+    /// whether or not that one reaches the threshold.
+    pub fn best_match<'a>(
+        &self,
+        query: &MatchQuery,
+        candidates: &'a [Place],
+        threshold: f64,
+    ) -> BestMatch<'a> {
+        let mut ranked = self.rank(query, candidates);
+
+        let best = match ranked.first() {
+            Some(best) if best.score.total >= threshold => best.score.total,
+            _ => return BestMatch::NotFound,
+        };
+
+        ranked.retain(|candidate| best - candidate.score.total < self.margin);
+
+        if ranked.len() == 1 {
+            BestMatch::Unique(ranked.remove(0))
+        } else {
+            BestMatch::Ambiguous(ranked)
+        }
+    }
+}
+
+impl Response {
+    /// Every candidate scored against `query` with the default matcher, best first.
+    pub fn rank(&self, query: &MatchQuery) -> Vec<ScoredCandidate<'_>> {
+        Matcher::new().rank(query, &self.candidates)
+    }
+
+    /// The candidate matching `query` with a score of at least `threshold`, refusing to pick between close ones.
+    pub fn best_match(&self, query: &MatchQuery, threshold: f64) -> BestMatch<'_> {
+        Matcher::new().best_match(query, &self.candidates, threshold)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BestMatch, MatchQuery, Matcher};
+    use crate::models::LatLng;
+    use crate::place::Response;
+    use crate::testing;
+    use serde_json::json;
+
+    fn candidate(name: &str, address: &str, phone: &str, lat: f64, lng: f64) -> serde_json::Value {
+        testing::place_value(
+            LatLng::new(lat, lng),
+            json!({
+                "name": name,
+                "formatted_address": address,
+                "formatted_phone_number": phone
+            }),
+        )
+    }
+
+    fn response(candidates: Vec<serde_json::Value>) -> Response {
+        serde_json::from_value(json!({ "candidates": candidates, "status": "OK" })).unwrap()
+    }
+
+    fn branches() -> Response {
+        response(vec![
+            candidate(
+                "Gelato Messina",
+                "241 Victoria St, Darlinghurst NSW 2010, Australia",
+                "(02) 8354 1223",
+                -33.8792,
+                151.2216,
+            ),
+            candidate(
+                "Gelato Messina",
+                "1 Macquarie St, Sydney NSW 2000, Australia",
+                "(02) 9252 8180",
+                -33.8611,
+                151.2125,
+            ),
+        ])
+    }
+
+    #[test]
+    fn test_rank_breaks_down_signals() {
+        let query = MatchQuery::new("Gelato Messina")
+            .set_phone("+61 2 8354 1223")
+            .set_address("241 Victoria Street, Darlinghurst");
+
+        let response = branches();
+        let ranked = response.rank(&query);
+
+        assert_eq!(
+            ranked[0].place.formatted_phone_number.as_deref(),
+            Some("(02) 8354 1223")
+        );
+        assert_eq!(ranked[0].score.phone, Some(true));
+        assert_eq!(ranked[1].score.phone, Some(false));
+        assert_eq!(ranked[0].score.distance, None);
+        assert!(ranked[0].score.address > ranked[1].score.address);
+        assert!(ranked[0].score.total > ranked[1].score.total);
+    }
+
+    #[test]
+    fn test_best_match() {
+        let response = branches();
+
+        let near_quay =
+            MatchQuery::new("Gelato Messina").set_location(LatLng::new(-33.8615, 151.2120));
+        match response.best_match(&near_quay, 0.6) {
+            BestMatch::Unique(found) => assert_eq!(
+                found.place.formatted_phone_number.as_deref(),
+                Some("(02) 9252 8180")
+            ),
+            other => panic!("expected a unique match, got {:?}", other),
+        }
+
+        // the name alone fits every branch of the chain.
+        let name_only = MatchQuery::new("Gelato Messina");
+        match response.best_match(&name_only, 0.5) {
+            BestMatch::Ambiguous(candidates) => assert_eq!(candidates.len(), 2),
+            other => panic!("expected an ambiguous match, got {:?}", other),
+        }
+
+        let elsewhere = MatchQuery::new("Bourke Street Bakery").set_phone("(02) 9699 1011");
+        assert!(matches!(
+            response.best_match(&elsewhere, 0.6),
+            BestMatch::NotFound
+        ));
+    }
+
+    #[test]
+    fn test_margin_counts_candidates_below_threshold() {
+        // about 195 and 205 meters from the query, scoring about 0.61 and 0.59 on distance alone.
+        let response = response(vec![
+            candidate("Messina", "", "", 195.0 / 111_195.0, 0.0),
+            candidate("Messina", "", "", 205.0 / 111_195.0, 0.0),
+        ]);
+        let query = MatchQuery {
+            location: Some(LatLng::new(0.0, 0.0)),
+            ..Default::default()
+        };
+
+        match response.best_match(&query, 0.6) {
+            BestMatch::Ambiguous(candidates) => {
+                assert_eq!(candidates.len(), 2);
+                assert!(candidates[1].score.total < 0.6);
+            }
+            other => panic!("expected an ambiguous match, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_missing_signals_score_nothing() {
+        let mut without_phone = candidate("Gelato Messina", "", "", -33.8792, 151.2216);
+        without_phone
+            .as_object_mut()
+            .unwrap()
+            .remove("formatted_phone_number");
+
+        let response = response(vec![
+            without_phone,
+            candidate("Gelato Messina", "", "(02) 8354 1223", -33.8611, 151.2125),
+        ]);
+        let query = MatchQuery::new("Gelato Messina").set_phone("(02) 8354 1223");
+
+        let ranked = Matcher::new().rank(&query, &response.candidates);
+        assert_eq!(ranked[0].score.total, 1.0);
+        assert_eq!(ranked[1].score.phone, None);
+        assert!(ranked[1].score.total < 0.7);
+
+        assert!(matches!(
+            response.best_match(&query, 0.6),
+            BestMatch::Unique(found) if found.score.phone == Some(true)
+        ));
+    }
+}
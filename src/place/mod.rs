@@ -1,5 +1,7 @@
+mod matching;
 mod request;
 mod response;
 
+pub use matching::*;
 pub use request::*;
 pub use response::*;
@@ -16,6 +16,9 @@ pub struct Request {
     pub input: String,
     pub input_type: String,
     pub fields: Vec<Field>,
+
+    /// The language in which to return results, such as "en" or "zh-TW".
+    pub language: Option<String>,
 }
 
 #[derive(Clone, ArgEnum, Display, Debug)]
@@ -63,11 +66,17 @@ impl Request {
 
         self
     }
+
+    pub fn set_language(&mut self, language: impl Into<String>) -> &mut Self {
+        self.language = Some(language.into());
+
+        self
+    }
 }
 
 impl SendUrl for Request {
     fn get_url(&self) -> &'static str {
-        return self.url;
+        self.url
     }
 }
 
@@ -81,7 +90,7 @@ impl SearchParams for Request {
 
         params.push(("inputtype".to_owned(), self.input_type.clone()));
 
-        if self.fields.len() > 0 {
+        if !self.fields.is_empty() {
             let fields = self
                 .fields
                 .iter()
@@ -92,6 +101,10 @@ impl SearchParams for Request {
             params.push(("fields".to_owned(), fields));
         }
 
+        if let Some(language) = &self.language {
+            params.push(("language".to_owned(), language.to_owned()))
+        }
+
         params
     }
 }
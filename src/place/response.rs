@@ -1,5 +1,6 @@
 use crate::models::*;
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Response {
@@ -7,4 +8,95 @@ pub struct Response {
     pub status: SearchStatus,
     pub error_message: Option<String>,
     pub info_messages: Option<Vec<String>>,
+
+    /// Fields returned by Google that this crate does not model yet.
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// Distances are measured from `origin`, usually the location the search was made around.
+impl Response {
+    /// The candidates that have a location, in Google's order, with their distance and bearing.
+    pub fn annotate(&self, origin: &LatLng) -> Vec<AnnotatedPlace<'_>> {
+        annotate_places(&self.candidates, origin)
+    }
+
+    /// Reorders the candidates from the nearest to the farthest.
+    pub fn sort_by_distance(&mut self, origin: &LatLng) {
+        sort_places_by_distance(&mut self.candidates, origin);
+    }
+
+    /// Drops the candidates farther than `max_distance` meters.
+    pub fn retain_within(&mut self, origin: &LatLng, max_distance: f64) {
+        retain_places_within(&mut self.candidates, origin, max_distance);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Response;
+    use crate::models::SearchStatus;
+
+    const FIND: &str = r#"{
+        "candidates": [
+            {
+                "formatted_address": "140 George St, The Rocks NSW 2000, Australia",
+                "geometry": {
+                    "location": { "lat": -33.8599358, "lng": 151.2090295 },
+                    "viewport": {
+                        "northeast": { "lat": -33.85824377010728, "lng": 151.2104386798927 },
+                        "southwest": { "lat": -33.86094342989272, "lng": 151.2077390201073 }
+                    }
+                },
+                "name": "Museum of Contemporary Art Australia",
+                "opening_hours": { "open_now": false },
+                "rating": 4.4
+            }
+        ],
+        "status": "OK"
+    }"#;
+
+    #[test]
+    fn test_deserialize_find_response() {
+        let response: Response = serde_json::from_str(FIND).unwrap();
+
+        assert!(matches!(response.status, SearchStatus::Ok));
+        assert_eq!(response.candidates.len(), 1);
+
+        let candidate = &response.candidates[0];
+        assert_eq!(
+            candidate.name.as_deref(),
+            Some("Museum of Contemporary Art Australia")
+        );
+        assert_eq!(candidate.rating, Some(4.4));
+        assert!(candidate.place_id.is_none());
+    }
+
+    #[test]
+    fn test_deserialize_find_request_denied() {
+        let response: Response = serde_json::from_str(
+            r#"{
+                "candidates": [],
+                "error_message": "The provided API key is invalid.",
+                "status": "REQUEST_DENIED"
+            }"#,
+        )
+        .unwrap();
+
+        assert!(matches!(response.status, SearchStatus::RequestDenied));
+        assert_eq!(
+            response.error_message.as_deref(),
+            Some("The provided API key is invalid.")
+        );
+    }
+
+    #[test]
+    fn test_preserve_unknown_response_fields() {
+        let response: Response = serde_json::from_str(
+            r#"{ "candidates": [], "status": "ZERO_RESULTS", "debug_log": { "line": [] } }"#,
+        )
+        .unwrap();
+
+        assert!(response.extra.contains_key("debug_log"));
+    }
 }
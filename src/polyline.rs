@@ -0,0 +1,135 @@
+//! Google's [encoded polyline algorithm format](https://developers.google.com/maps/documentation/utilities/polylinealgorithm),
+//! with coordinates rounded to five decimal places.
+
+use crate::models::{Error, LatLng};
+
+const PRECISION: f64 = 1e5;
+
+fn encode_value(value: i64, output: &mut String) {
+    let mut value = if value < 0 { !(value << 1) } else { value << 1 };
+
+    while value >= 0x20 {
+        output.push(char::from((((value & 0x1f) | 0x20) + 63) as u8));
+
+        value >>= 5;
+    }
+
+    output.push(char::from((value + 63) as u8));
+}
+
+pub fn encode(path: &[LatLng]) -> String {
+    let mut output = String::new();
+    let (mut previous_lat, mut previous_lng) = (0, 0);
+
+    for point in path {
+        let lat = (point.lat * PRECISION).round() as i64;
+        let lng = (point.lng * PRECISION).round() as i64;
+
+        encode_value(lat - previous_lat, &mut output);
+        encode_value(lng - previous_lng, &mut output);
+
+        previous_lat = lat;
+        previous_lng = lng;
+    }
+
+    output
+}
+
+fn decode_value(bytes: &mut impl Iterator<Item = (usize, u8)>) -> Result<i64, Error> {
+    let mut result: i64 = 0;
+    let mut shift = 0;
+
+    loop {
+        let (index, byte) = bytes
+            .next()
+            .ok_or("polyline ends in the middle of a value")?;
+
+        if !(63..127).contains(&byte) || shift > 60 {
+            return Err(format!("invalid polyline character at {}", index).into());
+        }
+
+        let chunk = (byte - 63) as i64;
+
+        result |= (chunk & 0x1f) << shift;
+        shift += 5;
+
+        if chunk < 0x20 {
+            break;
+        }
+    }
+
+    Ok(if result & 1 == 1 {
+        !(result >> 1)
+    } else {
+        result >> 1
+    })
+}
+
+pub fn decode(polyline: &str) -> Result<Vec<LatLng>, Error> {
+    let mut bytes = polyline.bytes().enumerate().peekable();
+    let mut path = vec![];
+    let (mut lat, mut lng) = (0, 0);
+
+    while bytes.peek().is_some() {
+        lat += decode_value(&mut bytes)?;
+        lng += decode_value(&mut bytes)?;
+
+        let point = LatLng::new(lat as f64 / PRECISION, lng as f64 / PRECISION);
+
+        if point.lat.abs() > 90.0 || point.lng.abs() > 180.0 {
+            return Err(format!("polyline point {} is out of range", point).into());
+        }
+
+        path.push(point);
+    }
+
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode, encode};
+    use crate::models::LatLng;
+
+    /// The example from Google's documentation.
+    const ENCODED: &str = "_p~iF~ps|U_ulLnnqC_mqNvxq`@";
+
+    fn path() -> Vec<LatLng> {
+        vec![
+            LatLng::new(38.5, -120.2),
+            LatLng::new(40.7, -120.95),
+            LatLng::new(43.252, -126.453),
+        ]
+    }
+
+    #[test]
+    fn test_encode_polyline() {
+        assert_eq!(encode(&path()), ENCODED);
+        assert_eq!(encode(&[]), "");
+    }
+
+    #[test]
+    fn test_decode_polyline() {
+        assert_eq!(decode(ENCODED).unwrap(), path());
+        assert_eq!(decode("").unwrap(), vec![]);
+    }
+
+    #[test]
+    fn test_polyline_round_trip() {
+        let path = vec![
+            LatLng::new(-33.86882, 151.20929),
+            LatLng::new(-33.8568, 151.21527),
+            LatLng::new(0.0, 0.0),
+            LatLng::new(-0.00001, 179.99999),
+        ];
+
+        assert_eq!(decode(&encode(&path)).unwrap(), path);
+    }
+
+    #[test]
+    fn test_reject_invalid_polyline() {
+        assert!(decode("_p~iF~ps|U_ulL").is_err());
+        assert!(decode("_p~iF~ps|U_").is_err());
+        assert!(decode("ab cd").is_err());
+    }
+}
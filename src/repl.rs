@@ -0,0 +1,213 @@
+use crossterm::cursor::MoveUp;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use crossterm::queue;
+use crossterm::style::{Attribute, Print, SetAttribute};
+use crossterm::terminal::{self, Clear, ClearType};
+use google_place_api::autocomplete::Prediction;
+use std::io::Write;
+
+type Error = Box<dyn std::error::Error>;
+
+/// What a key press asks the REPL to do.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Action {
+    /// The input changed, predictions are due once the typing pauses.
+    Edit,
+
+    /// Only the screen needs updating.
+    Redraw,
+
+    /// The prediction at this index was picked.
+    Pick(usize),
+
+    Quit,
+
+    Nothing,
+}
+
+/// The text typed so far and the predictions for it.
+#[derive(Debug, Default)]
+pub struct Prompt {
+    pub input: String,
+    pub predictions: Vec<Prediction>,
+
+    /// Index of the highlighted prediction.
+    pub selected: usize,
+
+    /// Shown under the input, such as an error of the last request.
+    pub message: Option<String>,
+}
+
+impl Prompt {
+    pub fn set_predictions(&mut self, predictions: Vec<Prediction>) {
+        self.predictions = predictions;
+        self.selected = 0;
+        self.message = None;
+    }
+
+    pub fn handle(&mut self, key: KeyEvent) -> Action {
+        let control = key.modifiers.contains(KeyModifiers::CONTROL);
+
+        match key.code {
+            KeyCode::Esc => Action::Quit,
+            KeyCode::Char('c' | 'd') if control => Action::Quit,
+            KeyCode::Char('u') if control => {
+                self.input.clear();
+                Action::Edit
+            }
+            KeyCode::Char(c) if !control => {
+                self.input.push(c);
+                Action::Edit
+            }
+            KeyCode::Backspace => match self.input.pop() {
+                Some(_) => Action::Edit,
+                None => Action::Nothing,
+            },
+            KeyCode::Up | KeyCode::BackTab => {
+                self.selected = self.selected.saturating_sub(1);
+                Action::Redraw
+            }
+            KeyCode::Down | KeyCode::Tab => {
+                self.selected = (self.selected + 1).min(self.predictions.len().saturating_sub(1));
+                Action::Redraw
+            }
+            KeyCode::Enter if !self.predictions.is_empty() => Action::Pick(self.selected),
+            _ => Action::Nothing,
+        }
+    }
+
+    /// The lines under the input, the highlighted prediction marked.
+    fn lines(&self) -> Vec<(bool, String)> {
+        let mut lines = self
+            .predictions
+            .iter()
+            .enumerate()
+            .map(|(index, prediction)| {
+                let text = match &prediction.structured_formatting {
+                    Some(formatting) => match &formatting.secondary_text {
+                        Some(secondary) => format!("{} · {}", formatting.main_text, secondary),
+                        None => formatting.main_text.clone(),
+                    },
+                    None => prediction.description.clone(),
+                };
+
+                (index == self.selected, text)
+            })
+            .collect::<Vec<_>>();
+
+        if let Some(message) = &self.message {
+            lines.push((false, message.clone()));
+        }
+
+        lines
+    }
+
+    /// Draws the input and the predictions under it, leaving the cursor at the end of the input.
+    pub fn draw(&self, out: &mut impl Write) -> Result<(), Error> {
+        let width = terminal::size()
+            .map_or(80, |(width, _)| width as usize)
+            .max(4);
+        let prompt = format!("> {}", self.input);
+
+        queue!(out, Print("\r"), Clear(ClearType::FromCursorDown))?;
+
+        let lines = self.lines();
+        for (selected, line) in &lines {
+            let line = format!("{} {}", if *selected { ">" } else { " " }, line)
+                .chars()
+                .take(width - 1)
+                .collect::<String>();
+
+            queue!(out, Print("\r\n"))?;
+
+            if *selected {
+                queue!(
+                    out,
+                    SetAttribute(Attribute::Reverse),
+                    Print(line),
+                    SetAttribute(Attribute::Reset)
+                )?;
+            } else {
+                queue!(out, Print(line))?;
+            }
+        }
+
+        // the input goes last, for the cursor to end up after it.
+        queue!(
+            out,
+            MoveUp(lines.len() as u16),
+            Print("\r"),
+            Print(prompt.chars().take(width - 1).collect::<String>())
+        )?;
+
+        out.flush()?;
+
+        Ok(())
+    }
+
+    /// Removes the prompt from the screen.
+    pub fn erase(&self, out: &mut impl Write) -> Result<(), Error> {
+        queue!(out, Print("\r"), Clear(ClearType::FromCursorDown))?;
+        out.flush()?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Action, Prompt};
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+    use google_place_api::autocomplete::Prediction;
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    fn prediction(description: &str) -> Prediction {
+        serde_json::from_value(serde_json::json!({ "description": description })).unwrap()
+    }
+
+    #[test]
+    fn test_prompt_keys() {
+        let mut prompt = Prompt::default();
+
+        assert_eq!(prompt.handle(key(KeyCode::Enter)), Action::Nothing);
+        assert_eq!(prompt.handle(key(KeyCode::Char('o'))), Action::Edit);
+        assert_eq!(prompt.handle(key(KeyCode::Char('p'))), Action::Edit);
+        assert_eq!(prompt.handle(key(KeyCode::Backspace)), Action::Edit);
+        assert_eq!(prompt.input, "o");
+
+        prompt.set_predictions(vec![prediction("Opera Bar"), prediction("Opera House")]);
+        assert_eq!(prompt.handle(key(KeyCode::Up)), Action::Redraw);
+        assert_eq!(prompt.selected, 0);
+        prompt.handle(key(KeyCode::Down));
+        prompt.handle(key(KeyCode::Down));
+        assert_eq!(prompt.selected, 1);
+        assert_eq!(prompt.handle(key(KeyCode::Enter)), Action::Pick(1));
+
+        let control = |c| KeyEvent::new(KeyCode::Char(c), KeyModifiers::CONTROL);
+        assert_eq!(prompt.handle(control('u')), Action::Edit);
+        assert_eq!(prompt.input, "");
+        assert_eq!(prompt.handle(control('c')), Action::Quit);
+        assert_eq!(prompt.handle(key(KeyCode::Esc)), Action::Quit);
+    }
+
+    #[test]
+    fn test_draw_marks_selection() {
+        let mut prompt = Prompt {
+            input: "opera".to_owned(),
+            ..Default::default()
+        };
+        prompt.set_predictions(vec![prediction("Opera Bar"), prediction("Opera House")]);
+        prompt.selected = 1;
+
+        let mut screen = vec![];
+        prompt.draw(&mut screen).unwrap();
+        let screen = String::from_utf8(screen).unwrap();
+
+        assert!(screen.contains("  Opera Bar"));
+        assert!(screen.contains("> Opera House"));
+        assert!(screen.ends_with("> opera"));
+    }
+}
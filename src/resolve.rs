@@ -0,0 +1,435 @@
+//! Local matching of records that describe the same real-world place under different place_ids.
+
+use crate::models::Place;
+use std::collections::{BTreeMap, HashSet};
+
+/// Lowercases, turns punctuation into spaces and collapses whitespace, so that "Joe's Café - Downtown" reads "joe s café downtown".
+pub fn normalize_text(text: &str) -> String {
+    text.chars()
+        .map(|c| if c.is_alphanumeric() { c } else { ' ' })
+        .collect::<String>()
+        .to_lowercase()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Length of the country code at the start of international `digits`.
+/// Codes are one to three digits long, ITU zones 1 and 7 being single digits.
+fn country_code_len(digits: &str) -> usize {
+    const TWO_DIGIT_CODES: [&str; 44] = [
+        "20", "27", "30", "31", "32", "33", "34", "36", "39", "40", "41", "43", "44", "45", "46",
+        "47", "48", "49", "51", "52", "53", "54", "55", "56", "57", "58", "60", "61", "62", "63",
+        "64", "65", "66", "81", "82", "84", "86", "90", "91", "92", "93", "94", "95", "98",
+    ];
+
+    if digits.starts_with('1') || digits.starts_with('7') {
+        1
+    } else if TWO_DIGIT_CODES.iter().any(|code| digits.starts_with(code)) {
+        2
+    } else {
+        3
+    }
+}
+
+/// The national number of a phone number, without the country code or trunk prefix
+/// that separate "+61 2 9250 7111" from "(02) 9250 7111".
+pub fn normalize_phone(phone: &str) -> String {
+    let digits = phone
+        .chars()
+        .filter(|c| c.is_ascii_digit())
+        .collect::<String>();
+
+    let international = if phone.trim_start().starts_with('+') {
+        Some(digits.as_str())
+    } else {
+        digits.strip_prefix("00")
+    };
+
+    let national = match international {
+        Some(digits) => &digits[country_code_len(digits).min(digits.len())..],
+        // a North American number dialed with its trunk prefix.
+        None if digits.len() == 11 && digits.starts_with('1') => &digits[1..],
+        None => &digits,
+    };
+
+    // the trunk prefix, also written after the country code as in "+44 (0)20 7946 0000".
+    national.strip_prefix('0').unwrap_or(national).to_owned()
+}
+
+fn levenshtein(a: &[char], b: &[char]) -> usize {
+    let mut previous = (0..=b.len()).collect::<Vec<_>>();
+
+    for (i, x) in a.iter().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+
+        for (j, y) in b.iter().enumerate() {
+            current[j + 1] = (previous[j] + usize::from(x != y))
+                .min(previous[j + 1] + 1)
+                .min(current[j] + 1);
+        }
+
+        previous = current;
+    }
+
+    previous[b.len()]
+}
+
+/// Share of distinct words the two texts have in common.
+fn token_similarity(a: &str, b: &str) -> f64 {
+    let a = a.split_whitespace().collect::<HashSet<_>>();
+    let b = b.split_whitespace().collect::<HashSet<_>>();
+
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+
+    a.intersection(&b).count() as f64 / a.union(&b).count() as f64
+}
+
+/// Similarity of two texts between 0 and 1, after normalizing them, the best of an edit distance and a word overlap.
+pub fn text_similarity(a: &str, b: &str) -> f64 {
+    let (a, b) = (normalize_text(a), normalize_text(b));
+
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+
+    let (chars_a, chars_b) = (a.chars().collect::<Vec<_>>(), b.chars().collect::<Vec<_>>());
+    let longest = chars_a.len().max(chars_b.len());
+    let edit = 1.0 - levenshtein(&chars_a, &chars_b) as f64 / longest as f64;
+
+    edit.max(token_similarity(&a, &b))
+}
+
+/// Whether two phone numbers reach the same line, `None` if either has no digits.
+pub fn phones_match(a: &str, b: &str) -> Option<bool> {
+    let (a, b) = (normalize_phone(a), normalize_phone(b));
+
+    if a.is_empty() || b.is_empty() {
+        return None;
+    }
+
+    Some(a == b)
+}
+
+/// The phone number of a place, international first.
+pub(crate) fn phone_of(place: &Place) -> Option<&str> {
+    place
+        .international_phone_number
+        .as_deref()
+        .or(place.formatted_phone_number.as_deref())
+}
+
+/// The address of a place, formatted first.
+pub(crate) fn address_of(place: &Place) -> Option<&str> {
+    place
+        .formatted_address
+        .as_deref()
+        .or(place.vicinity.as_deref())
+}
+
+/// How much each signal weighs in the overall score.
+/// Signals missing from either record are left out, unless they are expected, see `Resolver::combine`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Weights {
+    pub name: f64,
+    pub distance: f64,
+    pub phone: f64,
+    pub address: f64,
+}
+
+impl Default for Weights {
+    fn default() -> Self {
+        Weights {
+            name: 0.4,
+            distance: 0.3,
+            phone: 0.2,
+            address: 0.1,
+        }
+    }
+}
+
+/// How alike two places are, each signal `None` when either record lacks it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PairScore {
+    /// Similarity of the normalized names, between 0 and 1.
+    pub name: Option<f64>,
+
+    /// Distance in meters between the two locations.
+    pub distance: Option<f64>,
+
+    pub phone: Option<bool>,
+
+    /// Similarity of the normalized addresses, between 0 and 1.
+    pub address: Option<f64>,
+
+    /// Weighted average of the available signals, between 0 and 1.
+    pub total: f64,
+}
+
+/// Scores pairs of places and clusters the likely duplicates.
+#[derive(Debug, Clone)]
+pub struct Resolver {
+    pub weights: Weights,
+
+    /// Locations this many meters apart or more score nothing on distance, and are never duplicates.
+    pub max_distance: f64,
+
+    /// Names less similar than this are never duplicates.
+    pub min_name_similarity: f64,
+
+    /// Overall score from which a pair is a duplicate.
+    pub min_score: f64,
+}
+
+impl Default for Resolver {
+    fn default() -> Self {
+        Resolver {
+            weights: Weights::default(),
+            max_distance: 50.0,
+            min_name_similarity: 0.7,
+            min_score: 0.75,
+        }
+    }
+}
+
+impl Resolver {
+    pub fn new() -> Resolver {
+        Resolver::default()
+    }
+
+    pub fn set_weights(mut self, weights: Weights) -> Resolver {
+        self.weights = weights;
+
+        self
+    }
+
+    pub fn set_max_distance(mut self, max_distance: f64) -> Resolver {
+        self.max_distance = max_distance;
+
+        self
+    }
+
+    pub fn set_min_name_similarity(mut self, min_name_similarity: f64) -> Resolver {
+        self.min_name_similarity = min_name_similarity;
+
+        self
+    }
+
+    pub fn set_min_score(mut self, min_score: f64) -> Resolver {
+        self.min_score = min_score;
+
+        self
+    }
+
+    /// Combines the available signals into a score.
+    /// A signal missing while `expected`, in the order name, distance, phone and address, scores nothing
+    /// instead of being left out.
+    pub(crate) fn combine(
+        &self,
+        name: Option<f64>,
+        distance: Option<f64>,
+        phone: Option<bool>,
+        address: Option<f64>,
+        expected: [bool; 4],
+    ) -> PairScore {
+        let signals = [
+            (self.weights.name, name),
+            (
+                self.weights.distance,
+                distance.map(|distance| (1.0 - distance / self.max_distance).max(0.0)),
+            ),
+            (
+                self.weights.phone,
+                phone.map(|phone| f64::from(u8::from(phone))),
+            ),
+            (self.weights.address, address),
+        ];
+
+        let (sum, weight) = signals
+            .iter()
+            .zip(expected)
+            .filter_map(|((weight, value), expected)| match value {
+                Some(value) => Some((weight * value, *weight)),
+                None if expected => Some((0.0, *weight)),
+                None => None,
+            })
+            .fold((0.0, 0.0), |(sum, total), (value, weight)| {
+                (sum + value, total + weight)
+            });
+
+        PairScore {
+            name,
+            distance,
+            phone,
+            address,
+            total: if weight == 0.0 { 0.0 } else { sum / weight },
+        }
+    }
+
+    pub fn score(&self, a: &Place, b: &Place) -> PairScore {
+        let name = a
+            .name
+            .as_deref()
+            .zip(b.name.as_deref())
+            .map(|(a, b)| text_similarity(a, b));
+        let distance = a
+            .location()
+            .zip(b.location())
+            .map(|(a, b)| a.haversine_distance(b));
+        let phone = phone_of(a)
+            .zip(phone_of(b))
+            .and_then(|(a, b)| phones_match(a, b));
+        let address = address_of(a)
+            .zip(address_of(b))
+            .map(|(a, b)| text_similarity(a, b));
+
+        self.combine(name, distance, phone, address, [false; 4])
+    }
+
+    /// Whether a scored pair describes the same place.
+    pub fn is_duplicate(&self, score: &PairScore) -> bool {
+        score.total >= self.min_score
+            && score
+                .name
+                .is_none_or(|name| name >= self.min_name_similarity)
+            && score
+                .distance
+                .is_none_or(|distance| distance < self.max_distance)
+    }
+
+    /// Groups of indices into `places` that are likely the same place, each with two members or more.
+    /// Duplicates chain, so two records can share a group through a third one.
+    ///
+    /// Every pair is compared, which is fine for the few thousand places a crawl returns.
+    pub fn cluster(&self, places: &[Place]) -> Vec<Vec<usize>> {
+        let mut parents = (0..places.len()).collect::<Vec<_>>();
+
+        fn root(parents: &mut [usize], mut index: usize) -> usize {
+            while parents[index] != index {
+                parents[index] = parents[parents[index]];
+                index = parents[index];
+            }
+
+            index
+        }
+
+        for i in 0..places.len() {
+            for j in i + 1..places.len() {
+                if self.is_duplicate(&self.score(&places[i], &places[j])) {
+                    let (a, b) = (root(&mut parents, i), root(&mut parents, j));
+                    parents[a.max(b)] = a.min(b);
+                }
+            }
+        }
+
+        let mut groups = BTreeMap::<usize, Vec<usize>>::new();
+
+        for index in 0..places.len() {
+            let group = root(&mut parents, index);
+            groups.entry(group).or_default().push(index);
+        }
+
+        groups
+            .into_values()
+            .filter(|group| group.len() > 1)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{normalize_phone, normalize_text, phones_match, text_similarity, Resolver};
+    use crate::models::{LatLng, Place};
+    use crate::testing;
+    use serde_json::json;
+
+    fn place(name: &str, lat: f64, lng: f64, phone: Option<&str>) -> Place {
+        testing::place(
+            LatLng::new(lat, lng),
+            json!({ "name": name, "formatted_phone_number": phone }),
+        )
+    }
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(
+            normalize_text("Joe's Café - Downtown!"),
+            "joe s café downtown"
+        );
+        assert_eq!(normalize_phone("+61 2 9250 7111"), "292507111");
+        assert_eq!(normalize_phone("(02) 9250 7111"), "292507111");
+        assert_eq!(
+            phones_match("+61 2 9250 7111", "(02) 9250 7111"),
+            Some(true)
+        );
+        assert_eq!(
+            phones_match("(02) 9250 7111", "(02) 9250 7112"),
+            Some(false)
+        );
+        assert_eq!(phones_match("n/a", "(02) 9250 7111"), None);
+
+        assert_eq!(normalize_phone("+1 212-555-1234"), "2125551234");
+        assert_eq!(normalize_phone("1 (212) 555-1234"), "2125551234");
+        assert_eq!(normalize_phone("0044 20 7946 0000"), "2079460000");
+        assert_eq!(normalize_phone("+44 (0)20 7946 0000"), "2079460000");
+        assert_eq!(
+            phones_match("+1 212-555-1234", "+1 312-555-1234"),
+            Some(false)
+        );
+        assert_eq!(
+            phones_match("+1 212-555-1234", "(212) 555-1234"),
+            Some(true)
+        );
+    }
+
+    #[test]
+    fn test_text_similarity() {
+        assert_eq!(text_similarity("McDonald's", "McDonalds"), 0.9);
+        assert_eq!(
+            text_similarity("Starbucks Coffee", "starbucks coffee."),
+            1.0
+        );
+        assert!(text_similarity("Coffee Starbucks", "Starbucks Coffee") >= 1.0);
+        assert!(text_similarity("Starbucks", "Pizza Hut") < 0.3);
+        assert_eq!(text_similarity("", "Pizza Hut"), 0.0);
+    }
+
+    #[test]
+    fn test_score_pair() {
+        let resolver = Resolver::new();
+
+        let a = place("McDonald's", -33.8700, 151.2000, Some("(02) 9250 7111"));
+        let b = place("McDonalds", -33.87009, 151.2000, Some("+61 2 9250 7111"));
+        let score = resolver.score(&a, &b);
+
+        assert_eq!(score.name, Some(0.9));
+        assert!((score.distance.unwrap() - 10.0).abs() < 0.1);
+        assert_eq!(score.phone, Some(true));
+        assert_eq!(score.address, None);
+        assert!(score.total > 0.85);
+        assert!(resolver.is_duplicate(&score));
+
+        // same chain, another branch.
+        let c = place("McDonald's", -33.8800, 151.2000, Some("(02) 9250 9999"));
+        assert!(!resolver.is_duplicate(&resolver.score(&a, &c)));
+    }
+
+    #[test]
+    fn test_cluster_duplicates() {
+        let places = vec![
+            place("Bills Surry Hills", -33.8845, 151.2120, None),
+            place("Pizza Hut", -33.8700, 151.2000, None),
+            place("bills - Surry Hills", -33.88455, 151.2120, None),
+            place("Bills, Surry Hills.", -33.8847, 151.2120, None),
+            place("Pizza Hut", -33.9000, 151.2000, None),
+            place("Domino's", -33.87001, 151.2000, None),
+        ];
+
+        assert_eq!(Resolver::new().cluster(&places), vec![vec![0, 2, 3]]);
+
+        let strict = Resolver::new().set_max_distance(8.0).set_min_score(0.6);
+        assert_eq!(strict.cluster(&places), vec![vec![0, 2]]);
+    }
+}
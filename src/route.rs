@@ -0,0 +1,416 @@
+use crate::models::{Error, LatLng, Place, EARTH_RADIUS};
+use crate::nearby;
+use crate::sweep::{search_circle, Circle};
+use futures::stream::{self, StreamExt};
+use std::collections::HashSet;
+use std::future::Future;
+
+/// A place found along a route.
+#[derive(Debug)]
+pub struct RoutePlace {
+    pub place: Place,
+
+    /// Distance in meters from the place to the closest point of the route.
+    pub distance_from_route: f64,
+
+    /// Distance in meters along the route, from its start to the point closest to the place.
+    pub position_along_route: f64,
+}
+
+#[derive(Debug, Default)]
+pub struct RouteResult {
+    /// Places within reach of the route, deduplicated by place_id, sorted by `position_along_route`.
+    pub places: Vec<RoutePlace>,
+
+    /// Number of nearby searches sent, failed ones included, each of them fetching up to three pages.
+    pub searches: usize,
+
+    /// Searches that failed, with the circle around the sample point each of them covered.
+    /// The places of every other search are kept, so the result is only missing these circles.
+    pub failed: Vec<(Circle, Error)>,
+}
+
+/// Points every `spacing` meters along the path, paired with their distance from its start.
+/// The first and last vertices are always included. A `spacing` under one meter, or NaN, is taken as one meter.
+pub fn sample_path(path: &[LatLng], spacing: f64) -> Vec<(LatLng, f64)> {
+    let spacing = spacing.max(1.0);
+    let mut samples = vec![];
+    let mut travelled = 0.0;
+    let mut next = 0.0;
+
+    for segment in path.windows(2) {
+        let (start, end) = (&segment[0], &segment[1]);
+        let length = start.haversine_distance(end);
+        let bearing = start.initial_bearing(end);
+
+        while next <= travelled + length {
+            samples.push((start.destination(bearing, next - travelled), next));
+
+            next += spacing;
+        }
+
+        travelled += length;
+    }
+
+    match (path.last(), samples.last()) {
+        (Some(last), Some((_, position))) if travelled - position > 1e-6 => {
+            samples.push((*last, travelled));
+        }
+        (Some(last), None) => samples.push((*last, 0.0)),
+        _ => {}
+    }
+
+    samples
+}
+
+/// The distance from `point` to the closest point of the path, and how far along the path that point is, both in meters.
+///
+/// Each segment is projected onto a plane tangent at its start, which is accurate for segments up to a few kilometers.
+pub fn locate_on_path(path: &[LatLng], point: &LatLng) -> Option<(f64, f64)> {
+    if path.len() == 1 {
+        return Some((path[0].haversine_distance(point), 0.0));
+    }
+
+    let mut travelled = 0.0;
+    let mut closest: Option<(f64, f64)> = None;
+
+    for segment in path.windows(2) {
+        let (start, end) = (&segment[0], &segment[1]);
+        let length = start.haversine_distance(end);
+
+        let scale = start.lat.to_radians().cos();
+        let project = |p: &LatLng| {
+            (
+                (p.lng - start.lng).to_radians() * scale * EARTH_RADIUS,
+                (p.lat - start.lat).to_radians() * EARTH_RADIUS,
+            )
+        };
+
+        let (bx, by) = project(end);
+        let (px, py) = project(point);
+        let squared = bx * bx + by * by;
+
+        let t = if squared == 0.0 {
+            0.0
+        } else {
+            ((px * bx + py * by) / squared).clamp(0.0, 1.0)
+        };
+
+        let nearest = LatLng::new(
+            start.lat + (end.lat - start.lat) * t,
+            start.lng + (end.lng - start.lng) * t,
+        );
+        let distance = nearest.haversine_distance(point);
+
+        if closest.is_none_or(|(best, _)| distance < best) {
+            closest = Some((distance, travelled + length * t));
+        }
+
+        travelled += length;
+    }
+
+    closest
+}
+
+/// Finds places along a route by running nearby searches around points sampled along it.
+pub struct RouteSearch {
+    /// Parameters shared by every search, its location is replaced by each sampled point.
+    pub request: nearby::Request,
+
+    /// The route to follow.
+    pub path: Vec<LatLng>,
+
+    /// Distance in meters between two consecutive searches.
+    pub spacing: f64,
+
+    /// Radius in meters of each search.
+    pub radius: u32,
+
+    /// Places farther than this many meters from the route are dropped, `radius` when unset.
+    pub max_distance: Option<f64>,
+
+    /// How many searches may run at the same time.
+    pub concurrency: usize,
+}
+
+impl RouteSearch {
+    pub fn new(request: nearby::Request, path: Vec<LatLng>) -> RouteSearch {
+        RouteSearch {
+            request,
+            path,
+            spacing: 1000.0,
+            radius: 1000,
+            max_distance: None,
+            concurrency: 4,
+        }
+    }
+
+    pub fn set_keyword(mut self, keyword: impl Into<String>) -> RouteSearch {
+        self.request.keyword = Some(keyword.into());
+
+        self
+    }
+
+    pub fn set_type(mut self, request_type: impl Into<String>) -> RouteSearch {
+        self.request.request_type = Some(request_type.into());
+
+        self
+    }
+
+    pub fn set_language(mut self, language: impl Into<String>) -> RouteSearch {
+        self.request.language = Some(language.into());
+
+        self
+    }
+
+    pub fn set_spacing(mut self, spacing: f64) -> RouteSearch {
+        self.spacing = spacing;
+
+        self
+    }
+
+    pub fn set_radius(mut self, radius: u32) -> RouteSearch {
+        self.radius = radius;
+
+        self
+    }
+
+    pub fn set_max_distance(mut self, max_distance: f64) -> RouteSearch {
+        self.max_distance = Some(max_distance);
+
+        self
+    }
+
+    pub fn set_concurrency(mut self, concurrency: usize) -> RouteSearch {
+        self.concurrency = concurrency;
+
+        self
+    }
+
+    /// The circles searched along the route.
+    pub fn circles(&self) -> Vec<Circle> {
+        sample_path(&self.path, self.spacing)
+            .into_iter()
+            .map(|(center, _)| Circle {
+                center,
+                radius: self.radius as f64,
+            })
+            .collect()
+    }
+
+    /// Runs the search against the Nearby Search API, ranking by prominence around each point.
+    pub async fn send(&self) -> RouteResult {
+        self.run(|circle| search_circle(self.request.clone(), circle))
+            .await
+    }
+
+    /// Runs the search with a custom `search`, which returns every place found within a circle.
+    ///
+    /// A failing search does not stop the others, it is recorded in `RouteResult::failed` instead.
+    pub async fn run<F, Fut>(&self, search: F) -> RouteResult
+    where
+        F: Fn(Circle) -> Fut,
+        Fut: Future<Output = Result<Vec<Place>, Error>>,
+    {
+        let max_distance = self.max_distance.unwrap_or(self.radius as f64);
+
+        let searches = self.circles().into_iter().map(|circle| {
+            let search = search(circle);
+
+            async move { (circle, search.await) }
+        });
+
+        let outcomes = stream::iter(searches)
+            .buffer_unordered(self.concurrency.max(1))
+            .collect::<Vec<_>>()
+            .await;
+
+        let mut result = RouteResult::default();
+        let mut seen = HashSet::new();
+
+        for (circle, places) in outcomes {
+            result.searches += 1;
+
+            let places = match places {
+                Ok(places) => places,
+                Err(error) => {
+                    result.failed.push((circle, error));
+                    continue;
+                }
+            };
+
+            for place in places {
+                let located = place
+                    .location()
+                    .and_then(|location| locate_on_path(&self.path, location));
+
+                let (distance_from_route, position_along_route) = match located {
+                    Some(located) if located.0 <= max_distance => located,
+                    _ => continue,
+                };
+
+                if let Some(place_id) = &place.place_id {
+                    if !seen.insert(place_id.clone()) {
+                        continue;
+                    }
+                }
+
+                result.places.push(RoutePlace {
+                    place,
+                    distance_from_route,
+                    position_along_route,
+                });
+            }
+        }
+
+        result
+            .places
+            .sort_by(|a, b| a.position_along_route.total_cmp(&b.position_along_route));
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{locate_on_path, sample_path};
+    use crate::models::{Error, LatLng, Place, EARTH_RADIUS};
+    use crate::sweep::Circle;
+    use crate::testing::{self, assert_close};
+    use crate::{polyline, Client};
+    use serde_json::json;
+
+    /// An L-shaped route along the equator then north, about 11 km on each leg.
+    fn route() -> Vec<LatLng> {
+        vec![
+            LatLng::new(0.0, 0.0),
+            LatLng::new(0.0, 0.1),
+            LatLng::new(0.1, 0.1),
+        ]
+    }
+
+    fn place(place_id: &str, location: LatLng) -> Place {
+        testing::place(location, json!({ "place_id": place_id }))
+    }
+
+    /// Behaves like Nearby Search over a handful of gas stations.
+    async fn search(circle: Circle) -> Result<Vec<Place>, Error> {
+        let stations = vec![
+            place("start", LatLng::new(0.001, 0.0)),
+            place("corner", LatLng::new(0.0005, 0.1005)),
+            place("north", LatLng::new(0.08, 0.099)),
+            place("far", LatLng::new(0.05, 0.05)),
+        ];
+
+        Ok(stations
+            .into_iter()
+            .filter(|station| circle.contains(station.location().unwrap()))
+            .collect())
+    }
+
+    #[test]
+    fn test_sample_path() {
+        let leg = EARTH_RADIUS * 0.1_f64.to_radians();
+        let samples = sample_path(&route(), 1000.0);
+
+        assert_eq!(samples.first().unwrap().0, LatLng::new(0.0, 0.0));
+        assert_eq!(samples.last().unwrap().0, LatLng::new(0.1, 0.1));
+        assert_close(samples.last().unwrap().1, 2.0 * leg, 1e-6);
+
+        for pair in samples.windows(2) {
+            assert!(pair[1].1 - pair[0].1 <= 1000.0 + 1e-6);
+            assert!(pair[0].0.haversine_distance(&pair[1].0) <= 1000.0 + 1e-6);
+        }
+
+        for spacing in [0.0, -5.0, f64::NAN] {
+            assert_eq!(
+                sample_path(&route(), spacing).len(),
+                sample_path(&route(), 1.0).len()
+            );
+        }
+
+        assert_eq!(
+            sample_path(&[LatLng::new(1.0, 1.0)], 1000.0),
+            vec![(LatLng::new(1.0, 1.0), 0.0)]
+        );
+    }
+
+    #[test]
+    fn test_locate_on_path() {
+        let leg = EARTH_RADIUS * 0.1_f64.to_radians();
+
+        let (distance, position) = locate_on_path(&route(), &LatLng::new(0.001, 0.05)).unwrap();
+        assert_close(distance, EARTH_RADIUS * 0.001_f64.to_radians(), 0.5);
+        assert_close(position, leg / 2.0, 0.5);
+
+        let (distance, position) = locate_on_path(&route(), &LatLng::new(0.05, 0.101)).unwrap();
+        assert_close(distance, EARTH_RADIUS * 0.001_f64.to_radians(), 0.5);
+        assert_close(position, leg * 1.5, 0.5);
+
+        // before the start of the route.
+        let (distance, position) = locate_on_path(&route(), &LatLng::new(0.0, -0.01)).unwrap();
+        assert_close(distance, leg / 10.0, 0.5);
+        assert_eq!(position, 0.0);
+    }
+
+    #[tokio::test]
+    async fn test_search_along_route() {
+        let encoded = polyline::encode(&route());
+
+        let result = Client::new("hello kirby")
+            .search_polyline(&encoded)
+            .unwrap()
+            .set_type("gas_station")
+            .set_spacing(2000.0)
+            .set_radius(1500)
+            .run(search)
+            .await;
+
+        let ids = result
+            .places
+            .iter()
+            .map(|found| found.place.place_id.as_deref().unwrap())
+            .collect::<Vec<_>>();
+
+        assert_eq!(ids, vec!["start", "corner", "north"]);
+        assert!(result.searches > 10);
+        assert!(result
+            .places
+            .iter()
+            .all(|found| found.distance_from_route <= 1500.0));
+        assert!(result.failed.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_search_along_route_keeps_places_on_failure() {
+        let route_search = Client::new("hello kirby")
+            .search_route(route())
+            .set_spacing(2000.0)
+            .set_radius(1500);
+        let circles = route_search.circles().len();
+        let failing = LatLng::new(0.0, 0.0);
+
+        let result = route_search
+            .run(|circle| async move {
+                if circle.center == failing {
+                    return Err("connection reset".into());
+                }
+
+                search(circle).await
+            })
+            .await;
+
+        assert_eq!(result.searches, circles);
+        assert_eq!(result.failed.len(), 1);
+        assert_eq!(result.failed[0].0.center, failing);
+        assert_eq!(result.failed[0].1.to_string(), "connection reset");
+
+        let ids = result
+            .places
+            .iter()
+            .map(|found| found.place.place_id.as_deref().unwrap())
+            .collect::<Vec<_>>();
+
+        assert_eq!(ids, vec!["corner", "north"]);
+    }
+}
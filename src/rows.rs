@@ -0,0 +1,382 @@
+use google_place_api::export::{complete_csv_len, parse_csv, to_csv_row};
+use serde_json::{Map, Value};
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+
+type Error = Box<dyn std::error::Error>;
+
+/// A record of the batch input, by column name.
+pub type Row = Map<String, Value>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Csv,
+    Ndjson,
+}
+
+impl Format {
+    /// Tells the format from the file extension.
+    pub fn of(path: &Path) -> Result<Format, Error> {
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("csv") => Ok(Format::Csv),
+            Some("ndjson" | "jsonl") => Ok(Format::Ndjson),
+            _ => Err(format!(
+                "cannot tell the format of {}, expected a .csv, .ndjson or .jsonl file",
+                path.display()
+            )
+            .into()),
+        }
+    }
+}
+
+/// The rows of a batch input, and its columns in the order they first appear.
+#[derive(Debug)]
+pub struct Rows {
+    pub format: Format,
+    pub header: Vec<String>,
+    pub rows: Vec<Row>,
+}
+
+impl Rows {
+    pub fn read(path: &Path) -> Result<Rows, Error> {
+        let format = Format::of(path)?;
+        let text = fs::read_to_string(path)
+            .map_err(|error| format!("cannot read {}: {}", path.display(), error))?;
+
+        match format {
+            Format::Csv => Rows::from_csv(&text),
+            Format::Ndjson => Rows::from_ndjson(&text),
+        }
+    }
+
+    fn from_csv(text: &str) -> Result<Rows, Error> {
+        let mut records = parse_csv(text)?.into_iter();
+        let header = records.next().ok_or("the input has no header row")?;
+
+        let rows = records
+            .enumerate()
+            .map(|(index, record)| {
+                if record.len() != header.len() {
+                    return Err(format!(
+                        "row {} has {} fields, the header has {}",
+                        index + 1,
+                        record.len(),
+                        header.len()
+                    ));
+                }
+
+                Ok(header
+                    .iter()
+                    .cloned()
+                    .zip(record.into_iter().map(Value::String))
+                    .collect())
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Rows {
+            format: Format::Csv,
+            header,
+            rows,
+        })
+    }
+
+    fn from_ndjson(text: &str) -> Result<Rows, Error> {
+        let mut header = vec![];
+        let mut rows = vec![];
+
+        for (index, line) in text.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let row = match serde_json::from_str(line) {
+                Ok(Value::Object(row)) => row,
+                _ => return Err(format!("line {} is not a JSON object", index + 1).into()),
+            };
+
+            for column in row.keys() {
+                if !header.contains(column) {
+                    header.push(column.clone());
+                }
+            }
+
+            rows.push(row);
+        }
+
+        Ok(Rows {
+            format: Format::Ndjson,
+            header,
+            rows,
+        })
+    }
+}
+
+/// A value as a spreadsheet cell, strings unquoted and null empty.
+pub fn cell(value: Option<&Value>) -> String {
+    match value {
+        None | Some(Value::Null) => String::new(),
+        Some(Value::String(text)) => text.clone(),
+        Some(value) => value.to_string(),
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Part {
+    Text(String),
+    Column(String),
+}
+
+/// A text with columns named in braces, such as "{name} {address}".
+#[derive(Debug)]
+pub struct Template {
+    parts: Vec<Part>,
+}
+
+impl Template {
+    pub fn parse(template: &str) -> Result<Template, Error> {
+        let mut parts = vec![];
+        let mut rest = template;
+
+        while let Some(start) = rest.find('{') {
+            let end = rest[start..]
+                .find('}')
+                .map(|end| start + end)
+                .ok_or_else(|| format!("unclosed {{ in the template {:?}", template))?;
+
+            let column = rest[start + 1..end].trim();
+            if column.is_empty() {
+                return Err(format!("empty {{}} in the template {:?}", template).into());
+            }
+
+            parts.push(Part::Text(rest[..start].to_owned()));
+            parts.push(Part::Column(column.to_owned()));
+            rest = &rest[end + 1..];
+        }
+
+        parts.push(Part::Text(rest.to_owned()));
+        parts.retain(|part| *part != Part::Text(String::new()));
+
+        Ok(Template { parts })
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = &str> {
+        self.parts.iter().filter_map(|part| match part {
+            Part::Column(column) => Some(column.as_str()),
+            Part::Text(_) => None,
+        })
+    }
+
+    /// Fills in the columns of `row`, missing ones left empty, with whitespace collapsed.
+    pub fn render(&self, row: &Row) -> String {
+        self.parts
+            .iter()
+            .map(|part| match part {
+                Part::Text(text) => text.clone(),
+                Part::Column(column) => cell(row.get(column)),
+            })
+            .collect::<String>()
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+/// The batch output, written one row at a time so that an interrupted run can be resumed.
+pub struct Output {
+    format: Format,
+    header: Vec<String>,
+    file: File,
+}
+
+impl Output {
+    /// Creates the output, or with `resume` reopens it and returns the rows it already holds.
+    /// A row cut short by an interruption is dropped.
+    pub fn open(
+        path: &Path,
+        format: Format,
+        header: Vec<String>,
+        resume: bool,
+    ) -> Result<(Output, Vec<Row>), Error> {
+        let exists = path.exists();
+
+        if exists && !resume {
+            return Err(format!(
+                "{} already exists, pass --resume to continue it",
+                path.display()
+            )
+            .into());
+        }
+
+        if !exists {
+            let mut file = File::create(path)?;
+
+            if format == Format::Csv {
+                file.write_all(to_csv_row(header.iter().cloned()).as_bytes())?;
+            }
+
+            return Ok((
+                Output {
+                    format,
+                    header,
+                    file,
+                },
+                vec![],
+            ));
+        }
+
+        let text = fs::read_to_string(path)?;
+        let complete = match format {
+            // a quoted field may hold line breaks of its own.
+            Format::Csv => &text[..complete_csv_len(&text)],
+            Format::Ndjson => &text[..text.rfind('\n').map_or(0, |end| end + 1)],
+        };
+
+        let written = match format {
+            Format::Csv => {
+                if parse_csv(complete)?.first() != Some(&header) {
+                    return Err(format!(
+                        "cannot resume {}, its columns differ from this run's",
+                        path.display()
+                    )
+                    .into());
+                }
+
+                Rows::from_csv(complete)?.rows
+            }
+            Format::Ndjson => Rows::from_ndjson(complete)?.rows,
+        };
+
+        if complete.len() < text.len() {
+            fs::write(path, complete)?;
+        }
+
+        let file = OpenOptions::new().append(true).open(path)?;
+
+        Ok((
+            Output {
+                format,
+                header,
+                file,
+            },
+            written,
+        ))
+    }
+
+    /// Replaces every row written so far with `rows`.
+    pub fn rewrite(&mut self, rows: &[Row]) -> Result<(), Error> {
+        self.file.set_len(0)?;
+
+        if self.format == Format::Csv {
+            self.file
+                .write_all(to_csv_row(self.header.iter().cloned()).as_bytes())?;
+        }
+
+        for row in rows {
+            self.write(row)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn write(&mut self, row: &Row) -> Result<(), Error> {
+        let line = match self.format {
+            Format::Csv => to_csv_row(self.header.iter().map(|column| cell(row.get(column)))),
+            Format::Ndjson => format!("{}\n", serde_json::to_string(row)?),
+        };
+
+        self.file.write_all(line.as_bytes())?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Format, Output, Row, Rows, Template};
+    use serde_json::json;
+
+    fn row(value: serde_json::Value) -> Row {
+        value.as_object().unwrap().clone()
+    }
+
+    #[test]
+    fn test_template() {
+        let template = Template::parse("{name}, { city } NSW").unwrap();
+        assert_eq!(template.columns().collect::<Vec<_>>(), vec!["name", "city"]);
+
+        let filled = row(json!({ "name": "Gelato  Messina", "city": "Sydney" }));
+        assert_eq!(template.render(&filled), "Gelato Messina, Sydney NSW");
+        assert_eq!(template.render(&row(json!({ "city": 2000 }))), ", 2000 NSW");
+
+        assert!(Template::parse("{name").is_err());
+        assert!(Template::parse("{} {name}").is_err());
+    }
+
+    #[test]
+    fn test_read_rows() {
+        let rows = Rows::from_csv("name,city\r\nGelato Messina,Sydney\r\n").unwrap();
+        assert_eq!(rows.header, vec!["name", "city"]);
+        assert_eq!(rows.rows[0]["city"], "Sydney");
+        assert!(Rows::from_csv("name,city\nGelato Messina\n").is_err());
+
+        let rows = Rows::from_ndjson(
+            "{\"name\":\"Messina\"}\n\n{\"city\":\"Sydney\",\"name\":\"Bills\"}\n",
+        )
+        .unwrap();
+        assert_eq!(rows.header, vec!["name", "city"]);
+        assert_eq!(rows.rows.len(), 2);
+        assert!(Rows::from_ndjson("[1]").is_err());
+    }
+
+    #[test]
+    fn test_resume_output() {
+        let path =
+            std::env::temp_dir().join(format!("google-place-api-batch-{}.csv", std::process::id()));
+        let header = vec!["name".to_owned(), "match_status".to_owned()];
+
+        let (mut output, written) =
+            Output::open(&path, Format::Csv, header.clone(), false).unwrap();
+        assert!(written.is_empty());
+        output
+            .write(&row(
+                json!({ "name": "Messina\nSydney", "match_status": "OK" }),
+            ))
+            .unwrap();
+        drop(output);
+
+        // a row cut short by an interruption, inside a field holding a line break.
+        let mut text = std::fs::read_to_string(&path).unwrap();
+        text.push_str("\"Bills\nSurry");
+        std::fs::write(&path, text).unwrap();
+
+        assert!(Output::open(&path, Format::Csv, header.clone(), false).is_err());
+
+        let (mut output, written) = Output::open(&path, Format::Csv, header.clone(), true).unwrap();
+        assert_eq!(
+            written,
+            vec![row(
+                json!({ "name": "Messina\nSydney", "match_status": "OK" })
+            )]
+        );
+        output.write(&row(json!({ "name": "Bills" }))).unwrap();
+
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "name,match_status\r\n\"Messina\nSydney\",OK\r\nBills,\r\n"
+        );
+
+        output
+            .rewrite(&[row(json!({ "name": "Bills", "match_status": "OK" }))])
+            .unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "name,match_status\r\nBills,OK\r\n"
+        );
+
+        let other = vec!["name".to_owned()];
+        assert!(Output::open(&path, Format::Csv, other, true).is_err());
+
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,497 @@
+use crate::models::{Bounds, Error, LatLng, MultiPolygon, Place, SearchStatus};
+use crate::nearby;
+use futures::stream::{self, StreamExt};
+use std::collections::HashSet;
+use std::future::Future;
+
+/// Nearby Search returns at most 60 results, over three pages.
+pub const RESULT_LIMIT: usize = 60;
+
+/// Nearby Search ranked by prominence accepts a radius of at most 50 000 meters.
+pub const MAX_RADIUS: u32 = 50_000;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Circle {
+    pub center: LatLng,
+
+    /// Radius in meters.
+    pub radius: f64,
+}
+
+impl Circle {
+    pub fn contains(&self, point: &LatLng) -> bool {
+        self.center.haversine_distance(point) <= self.radius
+    }
+
+    /// Whether the circle and the bounds share any point.
+    pub fn intersects(&self, bounds: &Bounds) -> bool {
+        let lat = self
+            .center
+            .lat
+            .clamp(bounds.southwest.lat, bounds.northeast.lat);
+
+        // outside the bounds, the nearest longitude is the closer of their edges, going either way around.
+        let lng = if bounds.contains(&LatLng::new(lat, self.center.lng)) {
+            self.center.lng
+        } else {
+            let to_west = (bounds.southwest.lng - self.center.lng).rem_euclid(360.0);
+            let to_east = (self.center.lng - bounds.northeast.lng).rem_euclid(360.0);
+
+            if to_west < to_east {
+                bounds.southwest.lng
+            } else {
+                bounds.northeast.lng
+            }
+        };
+
+        self.contains(&LatLng::new(lat, lng))
+    }
+
+    /// The radius to search the circle with, capped at what Nearby Search accepts.
+    pub(crate) fn search_radius(&self) -> u32 {
+        (self.radius.ceil() as u32).min(MAX_RADIUS)
+    }
+}
+
+/// Every place a nearby search ranked by prominence finds within `circle`, following all pages.
+pub(crate) async fn search_circle(
+    mut request: nearby::Request,
+    circle: Circle,
+) -> Result<Vec<Place>, Error> {
+    request.location = circle.center;
+
+    let response = request
+        .prominence(circle.search_radius())
+        .send_all_pages()
+        .await?;
+
+    match response.status {
+        SearchStatus::Ok | SearchStatus::ZeroResults => Ok(response.results),
+        status => Err(format!(
+            "nearby search around {} failed with {:?}: {}",
+            circle.center,
+            status,
+            response.error_message.unwrap_or_default()
+        )
+        .into()),
+    }
+}
+
+/// The area a sweep covers, and keeps places from.
+#[derive(Debug, Clone)]
+pub enum Region {
+    Bounds(Bounds),
+    Circle(Circle),
+    Polygon(MultiPolygon),
+}
+
+impl Region {
+    pub fn bounds(&self) -> Bounds {
+        match self {
+            Region::Bounds(bounds) => *bounds,
+            Region::Circle(circle) => Bounds::from_circle(&circle.center, circle.radius),
+            Region::Polygon(polygon) => polygon.bounds(),
+        }
+    }
+
+    pub fn contains(&self, point: &LatLng) -> bool {
+        match self {
+            Region::Bounds(bounds) => bounds.contains(point),
+            Region::Circle(circle) => circle.contains(point),
+            Region::Polygon(polygon) => polygon.contains(point),
+        }
+    }
+
+    /// Whether a cell needs searching at all.
+    pub fn intersects(&self, cell: &Bounds) -> bool {
+        match self {
+            Region::Bounds(_) => true,
+            Region::Circle(circle) => circle.intersects(cell),
+            Region::Polygon(polygon) => polygon.intersects(cell),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct SweepResult {
+    /// Every place found inside the area, deduplicated by place_id.
+    pub places: Vec<Place>,
+
+    /// Number of nearby searches sent, failed ones included, each of them fetching up to three pages.
+    pub searches: usize,
+
+    /// Number of cells that returned a full 60 results and were split into four.
+    pub subdivided: usize,
+
+    /// Cells that still returned a full 60 results but could not be split below `min_radius`,
+    /// so they may hold more places than were found.
+    pub saturated: Vec<Bounds>,
+
+    /// Searches that failed, with the circle each of them covered.
+    /// The places of every other search are kept, so the sweep is only missing these circles.
+    pub failed: Vec<(Circle, Error)>,
+}
+
+/// Covers an area with overlapping nearby searches to collect more than the 60 results a single search allows.
+///
+/// The area is tiled with square cells searched through the circle around them.
+/// Any cell returning a full 60 results is split into four smaller cells and searched again,
+/// until cells reach `min_radius`.
+pub struct Sweep {
+    /// Parameters shared by every search, its location is replaced by the center of each cell.
+    pub request: nearby::Request,
+
+    /// The area to cover, only places inside it are kept.
+    pub region: Region,
+
+    /// Radius in meters of the circles the area is first covered with, at most `MAX_RADIUS`.
+    pub cell_radius: u32,
+
+    /// Cells are no longer split once their circle would be smaller than this radius in meters.
+    pub min_radius: u32,
+
+    /// How many searches may run at the same time.
+    pub concurrency: usize,
+}
+
+impl Sweep {
+    pub fn new(request: nearby::Request, region: Region) -> Sweep {
+        Sweep {
+            request,
+            region,
+            cell_radius: 1000,
+            min_radius: 50,
+            concurrency: 4,
+        }
+    }
+
+    pub fn set_keyword(mut self, keyword: impl Into<String>) -> Sweep {
+        self.request.keyword = Some(keyword.into());
+
+        self
+    }
+
+    pub fn set_type(mut self, request_type: impl Into<String>) -> Sweep {
+        self.request.request_type = Some(request_type.into());
+
+        self
+    }
+
+    pub fn set_language(mut self, language: impl Into<String>) -> Sweep {
+        self.request.language = Some(language.into());
+
+        self
+    }
+
+    pub fn set_cell_radius(mut self, cell_radius: u32) -> Sweep {
+        self.cell_radius = cell_radius;
+
+        self
+    }
+
+    pub fn set_min_radius(mut self, min_radius: u32) -> Sweep {
+        self.min_radius = min_radius;
+
+        self
+    }
+
+    pub fn set_concurrency(mut self, concurrency: usize) -> Sweep {
+        self.concurrency = concurrency;
+
+        self
+    }
+
+    /// The initial grid of cells touching the region, each small enough for its circle to have about `cell_radius`.
+    pub fn cells(&self) -> Vec<Bounds> {
+        let area = self.region.bounds();
+        let side = self.cell_radius.min(MAX_RADIUS) as f64 * std::f64::consts::SQRT_2;
+
+        let rows = (area.height() / side).ceil().max(1.0) as usize;
+        let cols = (area.width() / side).ceil().max(1.0) as usize;
+
+        area.split(rows, cols)
+            .into_iter()
+            .filter(|cell| self.region.intersects(cell))
+            .collect()
+    }
+
+    /// Whether a place found by one of the searches belongs to the swept region.
+    pub(crate) fn keeps(&self, place: &Place) -> bool {
+        place
+            .location()
+            .is_some_and(|location| self.region.contains(location))
+    }
+
+    /// Runs the sweep against the Nearby Search API, ranking by prominence within each circle.
+    pub async fn send(&self) -> SweepResult {
+        self.run(|circle| search_circle(self.request.clone(), circle))
+            .await
+    }
+
+    /// Runs the sweep with a custom `search`, which returns every place found within a circle.
+    ///
+    /// A failing search does not stop the sweep, it is recorded in `SweepResult::failed` instead.
+    pub async fn run<F, Fut>(&self, search: F) -> SweepResult
+    where
+        F: Fn(Circle) -> Fut,
+        Fut: Future<Output = Result<Vec<Place>, Error>>,
+    {
+        let mut result = SweepResult::default();
+        let mut seen = HashSet::new();
+        let mut cells = self.cells();
+
+        while !cells.is_empty() {
+            let searches = cells.into_iter().map(|cell| {
+                let circle = Circle {
+                    center: cell.center(),
+                    radius: cell.covering_radius(),
+                };
+                let search = search(circle);
+
+                async move { (cell, circle, search.await) }
+            });
+
+            let outcomes = stream::iter(searches)
+                .buffer_unordered(self.concurrency.max(1))
+                .collect::<Vec<_>>()
+                .await;
+
+            cells = vec![];
+
+            for (cell, circle, places) in outcomes {
+                result.searches += 1;
+
+                let places = match places {
+                    Ok(places) => places,
+                    Err(error) => {
+                        result.failed.push((circle, error));
+                        continue;
+                    }
+                };
+
+                if places.len() >= RESULT_LIMIT {
+                    if circle.radius / 2.0 >= self.min_radius as f64 {
+                        result.subdivided += 1;
+
+                        cells.extend(
+                            cell.split(2, 2)
+                                .into_iter()
+                                .filter(|quadrant| self.region.intersects(quadrant)),
+                        );
+                    } else {
+                        result.saturated.push(cell);
+                    }
+                }
+
+                for place in places {
+                    if !self.keeps(&place) {
+                        continue;
+                    }
+
+                    let is_new = match &place.place_id {
+                        Some(place_id) => seen.insert(place_id.clone()),
+                        None => true,
+                    };
+
+                    if is_new {
+                        result.places.push(place);
+                    }
+                }
+            }
+        }
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Circle, Region, Sweep, MAX_RADIUS, RESULT_LIMIT};
+    use crate::models::{Bounds, Error, LatLng, MultiPolygon, Place};
+    use crate::testing;
+    use crate::Client;
+    use serde_json::json;
+
+    /// A 30 by 30 grid of places, about 50 meters apart, in central Sydney.
+    fn places() -> Vec<LatLng> {
+        (0..30)
+            .flat_map(|row| (0..30).map(move |col| (row, col)))
+            .map(|(row, col)| {
+                LatLng::new(-33.88 + row as f64 * 0.00045, 151.20 + col as f64 * 0.00054)
+            })
+            .collect()
+    }
+
+    /// Behaves like Nearby Search: every place within the circle, but never more than 60.
+    async fn search(circle: Circle) -> Result<Vec<Place>, Error> {
+        Ok(places()
+            .iter()
+            .enumerate()
+            .filter(|(_, location)| circle.contains(location))
+            .map(|(index, location)| {
+                testing::place(*location, json!({ "place_id": format!("place-{}", index) }))
+            })
+            .take(RESULT_LIMIT)
+            .collect())
+    }
+
+    fn area() -> Bounds {
+        Bounds::new(
+            LatLng::new(-33.8801, 151.1999),
+            LatLng::new(-33.8668, 151.2158),
+        )
+    }
+
+    #[test]
+    fn test_sweep_cells_cover_area() {
+        let sweep = Client::new("hello kirby")
+            .sweep(area())
+            .set_cell_radius(500);
+        let cells = sweep.cells();
+
+        assert!(cells.len() > 1);
+        assert!(cells.iter().all(|cell| cell.covering_radius() <= 500.0));
+    }
+
+    #[tokio::test]
+    async fn test_sweep_collects_beyond_result_limit() {
+        let result = Client::new("hello kirby")
+            .sweep(area())
+            .set_cell_radius(1000)
+            .set_concurrency(3)
+            .run(search)
+            .await;
+
+        assert_eq!(result.places.len(), 900);
+        assert!(result.subdivided > 0);
+        assert!(result.searches > 1);
+
+        let mut ids = result
+            .places
+            .iter()
+            .map(|place| place.place_id.clone().unwrap())
+            .collect::<Vec<_>>();
+        ids.sort();
+        ids.dedup();
+        assert_eq!(ids.len(), 900);
+        assert!(result.saturated.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_sweep_around_keeps_places_in_circle() {
+        let center = LatLng::new(-33.8735, 151.2078);
+
+        let result = Client::new("hello kirby")
+            .sweep_around(center, 300.0)
+            .run(search)
+            .await;
+
+        let expected = places()
+            .iter()
+            .filter(|location| center.haversine_distance(location) <= 300.0)
+            .count();
+
+        assert_eq!(result.places.len(), expected);
+    }
+
+    #[tokio::test]
+    async fn test_sweep_stops_at_min_radius() {
+        let result = Sweep::new(
+            Client::new("hello kirby").nearby(0.0, 0.0),
+            Region::Bounds(area()),
+        )
+        .set_cell_radius(1000)
+        .set_min_radius(1000)
+        .run(search)
+        .await;
+
+        assert_eq!(result.subdivided, 0);
+        assert!(!result.saturated.is_empty());
+        assert!(result.places.len() < 900);
+    }
+
+    #[tokio::test]
+    async fn test_sweep_polygon() {
+        // a triangle over the lower-left half of the grid.
+        let polygon = MultiPolygon::from_geojson(
+            r#"{
+                "type": "Polygon",
+                "coordinates": [[[151.1999, -33.8801], [151.2158, -33.8801], [151.1999, -33.8668], [151.1999, -33.8801]]]
+            }"#,
+        )
+        .unwrap();
+
+        let client = Client::new("hello kirby");
+        let sweep = client.sweep_polygon(polygon.clone()).set_cell_radius(300);
+        let bounded = client.sweep(polygon.bounds()).set_cell_radius(300);
+
+        assert!(sweep.cells().len() < bounded.cells().len());
+
+        let result = sweep.run(search).await;
+
+        let expected = places()
+            .iter()
+            .filter(|location| polygon.contains(location))
+            .count();
+
+        assert!(expected > 0);
+        assert_eq!(result.places.len(), expected);
+        assert!(result
+            .places
+            .iter()
+            .all(|place| polygon.contains(place.location().unwrap())));
+    }
+
+    #[test]
+    fn test_sweep_cells_across_antimeridian() {
+        let sweep = Client::new("hello kirby").sweep_around(LatLng::new(0.0, 179.999), 2000.0);
+        let cells = sweep.cells();
+
+        assert!(cells.iter().any(|cell| cell.center().lng > 0.0));
+        assert!(cells.iter().any(|cell| cell.center().lng < 0.0));
+
+        let circle = Circle {
+            center: LatLng::new(0.0, 179.999),
+            radius: 2000.0,
+        };
+        let east = Bounds::new(LatLng::new(-0.01, -179.99), LatLng::new(0.01, -179.98));
+        let far = Bounds::new(LatLng::new(-0.01, -170.0), LatLng::new(0.01, -169.0));
+        assert!(circle.intersects(&east));
+        assert!(!circle.intersects(&far));
+    }
+
+    #[test]
+    fn test_search_radius_is_capped() {
+        let circle = Circle {
+            center: LatLng::new(0.0, 0.0),
+            radius: 80_000.0,
+        };
+
+        assert_eq!(circle.search_radius(), MAX_RADIUS);
+    }
+
+    #[tokio::test]
+    async fn test_sweep_keeps_places_of_other_cells_on_failure() {
+        let sweep = Client::new("hello kirby")
+            .sweep(area())
+            .set_cell_radius(300);
+        let cells = sweep.cells().len();
+        let failing = sweep.cells()[0].center();
+
+        let result = sweep
+            .run(|circle| async move {
+                if circle.center == failing {
+                    return Err("connection reset".into());
+                }
+
+                search(circle).await
+            })
+            .await;
+
+        assert_eq!(result.failed.len(), 1);
+        assert_eq!(result.failed[0].0.center, failing);
+        assert_eq!(result.failed[0].1.to_string(), "connection reset");
+        assert_eq!(result.searches, cells + 4 * result.subdivided);
+        assert!(!result.places.is_empty());
+    }
+}
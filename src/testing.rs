@@ -0,0 +1,35 @@
+//! Fixtures shared by the unit tests.
+
+use crate::models::{LatLng, Place};
+use serde_json::{json, Value};
+
+/// The JSON of a place located at `location`, with the other `fields` it has.
+pub fn place_value(location: LatLng, fields: Value) -> Value {
+    let mut value = json!({
+        "geometry": {
+            "location": location,
+            "viewport": { "northeast": location, "southwest": location }
+        }
+    });
+
+    if let (Some(place), Value::Object(fields)) = (value.as_object_mut(), fields) {
+        place.extend(fields);
+    }
+
+    value
+}
+
+/// A place located at `location`, with the other `fields` it has.
+pub fn place(location: LatLng, fields: Value) -> Place {
+    serde_json::from_value(place_value(location, fields)).unwrap()
+}
+
+pub fn assert_close(left: f64, right: f64, tolerance: f64) {
+    assert!(
+        (left - right).abs() <= tolerance,
+        "{} is not within {} of {}",
+        left,
+        tolerance,
+        right
+    );
+}
@@ -0,0 +1,70 @@
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tokio::time::Instant;
+
+/// Spaces out requests so that no more than a given number start per second, however many tasks share it.
+#[derive(Debug)]
+pub struct RateLimiter {
+    interval: Duration,
+    next: Mutex<Instant>,
+}
+
+impl RateLimiter {
+    /// # Panics
+    ///
+    /// When `per_second` is not a number above 0.
+    pub fn new(per_second: f64) -> RateLimiter {
+        assert!(
+            per_second > 0.0,
+            "the rate limit must be above 0, not {}",
+            per_second
+        );
+
+        RateLimiter {
+            interval: Duration::from_secs_f64(1.0 / per_second),
+            next: Mutex::new(Instant::now()),
+        }
+    }
+
+    /// Waits until the next request may start.
+    pub async fn acquire(&self) {
+        let start = {
+            let mut next = self.next.lock().await;
+            let start = (*next).max(Instant::now());
+
+            *next = start + self.interval;
+
+            start
+        };
+
+        tokio::time::sleep_until(start).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RateLimiter;
+    use futures::future::join_all;
+    use std::time::Duration;
+    use tokio::time::Instant;
+
+    #[tokio::test(start_paused = true)]
+    async fn test_rate_limiter_spaces_requests() {
+        let limiter = RateLimiter::new(4.0);
+        let start = Instant::now();
+
+        let started = join_all((0..5).map(|_| async {
+            limiter.acquire().await;
+
+            Instant::now() - start
+        }))
+        .await;
+
+        assert_eq!(
+            started,
+            (0..5)
+                .map(|i| Duration::from_millis(250 * i))
+                .collect::<Vec<_>>()
+        );
+    }
+}